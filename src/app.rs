@@ -1,9 +1,37 @@
+use std::path::PathBuf;
+
+use chrono::Local;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::Rect;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
 
-use crate::config::{load_config, save_config, Config, ReplayConfig, ReplayInput, RepoConfig};
-use crate::domain::{InputField, Repo, Workflow};
-use crate::service::github::GitHubService;
+use crate::cli::CliArgs;
+use crate::config::{
+    load_config_from, merge_layers, read_config_from, save_config_to, untrusted_team_commands, Config, ConfigError,
+    ConfigPaths, ConfigSource, FileStamp, HookEvent, ReplayConfig, ReplayInput, RepoConfig,
+};
+use crate::domain::{InputField, Repo, RepoListing, RunFailure, Workflow};
+use crate::guardrails::{self, DispatchTarget, GuardCheck};
+use crate::history::{InputHistory, HISTORY_FILE};
+use crate::hooks::{matching_hooks, HookContext, HookRunner};
+use crate::input_rules::{self, InputSort};
+use crate::keymap::{Action, Keymap};
+use crate::sources;
+use crate::theme::{self, Theme};
+use crate::validation::{self, InputProblem};
+use crate::widgets::TextInput;
+use crate::service::github::{
+    command_preview, dispatch_args, dispatch_body, dispatch_inputs, extract_log_errors, parse_repo_reference,
+    validate_repo_name, GitHubService,
+};
+use crate::service::lookups::{Lookup, Lookups};
+use crate::service::run_tracker::{unix_now, RunTracker, TrackedRun, TrackerEvent};
+
+/// How many trailing log lines to show when a run's logs are first fetched.
+const LOG_TAIL_LINES: usize = 200;
+/// How many lines of context to show above a failure when jumping to it.
+const LOG_CONTEXT_LINES: usize = 10;
 
 #[derive(Debug, Default)]
 pub enum Focus {
@@ -15,12 +43,96 @@ pub enum Focus {
     Output,
 }
 
+/// One row of the Repos panel: a group header or a repo.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RepoRow {
+    Group { name: String, count: usize, collapsed: bool },
+    Repo(usize), // index into `AppData.repos`
+}
+
+/// Which repo attribute the rename popup edits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RepoField {
+    Alias,
+    Group,
+}
+
+#[derive(Debug, Clone)]
+pub struct RepoEdit {
+    pub repo: String, // "owner/repo" being edited
+    pub field: RepoField,
+    pub value: String,
+}
+
+/// State of the bulk-import popup. It first asks for an owner, then lists
+/// that owner's repos for multi-select.
+#[derive(Debug, Default)]
+pub struct RepoImport {
+    pub owner: String,                      // user or org; empty for your own repos
+    pub listings: Option<Vec<RepoListing>>, // `None` until fetched
+    pub filter: String,
+    pub filtered: Vec<usize>,               // indices into `listings` matching `filter`
+    pub checked: std::collections::HashSet<usize>,
+    pub list_state: ratatui::widgets::ListState,
+}
+
+/// Something the command palette can run.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaletteCommand {
+    Action(Action),
+    SwitchRepo(usize),         // index into `AppData.repos`
+    Replay(Box<ReplayConfig>), // one of the selected repo's replays, as listed
+    Refresh,                   // reload the selected repo's branches and workflows
+    ViewRuns,                  // recent dispatched runs of the selected workflow
+}
+
+#[derive(Debug, Clone)]
+pub struct PaletteEntry {
+    pub command: PaletteCommand,
+    pub label: String,
+    pub keys: Option<String>, // bound keys, shown next to the label
+}
+
+/// State of the command palette: every command available in the current
+/// context, fuzzy-filtered by `query`.
+#[derive(Debug, Default)]
+pub struct CommandPalette {
+    pub query: String,
+    pub entries: Vec<PaletteEntry>,
+    pub filtered: Vec<usize>, // indices into `entries` matching `query`
+    pub list_state: ratatui::widgets::ListState,
+}
+
+/// An input a dispatch sends with something other than its workflow default.
+#[derive(Debug, Clone)]
+pub struct InputChange {
+    pub name: String,
+    pub default_value: Option<String>, // None when the workflow's inputs are unknown
+    pub value: String,
+}
+
+/// Screen areas of the clickable parts of the UI, recorded on every render
+/// so mouse clicks can be mapped back to what was drawn there.
+#[derive(Debug, Clone, Default)]
+pub struct HitAreas {
+    pub repos: Rect,
+    pub branches: Rect,
+    pub workflows: Rect,
+    pub output: Rect,
+    pub input_fields: Vec<(Rect, usize)>,         // visible rows of each field in the inputs popup
+    pub input_options: Vec<(Rect, usize, usize)>, // (area, field, option) of choice options
+    pub confirm_button: Option<Rect>,
+    pub cancel_button: Option<Rect>,
+}
+
+/// How a line of the dispatch output is colored; the theme picks the color.
 #[derive(Debug, Clone, Copy, PartialEq)]
-pub enum DispatchOutputColor {
-    Green,
-    Yellow,
-    White,
-    Blue,
+pub enum OutputTone {
+    Success,
+    Warning,
+    Normal,
+    Info,
+    Error,
 }
 
 #[derive(Debug, Default)]
@@ -31,6 +143,12 @@ pub struct AppData {
     pub inputs: Vec<String>,
     pub input_fields: Vec<InputField>,
     pub replays_list: Vec<ReplayConfig>,
+    pub run_logs: Vec<String>,          // full log of the last fetched run
+    pub run_failures: Vec<RunFailure>,  // "why it failed" entries for that run
+    pub dry_run_hooks: Vec<String>,     // hooks a dry run would have fired
+    pub owner_logins: Option<Vec<String>>, // you and your orgs, fetched in the background when Add Repo first opens
+    pub repo_names: std::collections::HashMap<String, Vec<String>>, // lowercased owner → repo names
+    pub dynamic_values: std::collections::HashMap<String, Vec<String>>, // recent tags / commits, see `dynamic_values_key`
 }
 
 #[derive(Debug, Default)]
@@ -41,6 +159,7 @@ pub struct UiState {
     pub workflows_state: ratatui::widgets::ListState,
     pub inputs_state: ratatui::widgets::ListState,
     pub replays_state: ratatui::widgets::ListState,
+    pub failures_state: ratatui::widgets::ListState,
 
     pub focus: Focus,
 
@@ -48,7 +167,7 @@ pub struct UiState {
     pub output: Option<String>,
     pub output_is_error: bool,
     pub output_is_success: bool,
-    pub dispatch_output_lines: Vec<(String, DispatchOutputColor)>,
+    pub dispatch_output_lines: Vec<(String, OutputTone)>,
 
     // Popups
     pub show_add_repo_popup: bool,
@@ -56,53 +175,94 @@ pub struct UiState {
     pub show_confirm_dispatch: bool,
     pub show_help_popup: bool,
     pub show_replays_popup: bool,
+    pub show_failures_popup: bool,
+    pub config_error: Option<String>, // startup error screen for a broken config file
+    pub confirm_remove_repo: Option<String>, // repo awaiting a y/n before removal
+    pub repo_edit: Option<RepoEdit>,         // alias/group being typed
+    pub repo_import: Option<RepoImport>,     // bulk-import popup
+    pub command_palette: Option<CommandPalette>,
     
     // Popup state
-    pub add_repo_owner: String,
-    pub add_repo_name: String,
+    pub add_repo_owner: TextInput,
+    pub add_repo_name: TextInput,
     pub add_repo_focus_owner: bool, // true = owner field, false = repo field
+    pub add_repo_suggestion: usize, // highlighted autocomplete suggestion
     
     pub input_fields_selected: usize, // which input row is focused
     pub input_fields_editing: bool,   // whether we're typing into the value
+    pub input_editor: TextInput,      // the value being typed, copied back into the field
+    pub input_suggestion: usize,      // highlighted value suggestion while typing
+    pub input_problems: Vec<InputProblem>, // what blocked the last dispatch attempt, re-checked while shown
+    pub input_sort: InputSort,             // order of the inputs popup, kept across workflows
     
     pub dispatch_command_preview: String,
+    pub dispatch_changes: Vec<InputChange>, // inputs of the pending dispatch that differ from their defaults
+    pub dispatch_guard: Option<GuardCheck>, // set when a guardrail protects the pending dispatch
+    pub dispatch_target: Option<DispatchTarget>, // the pending dispatch, re-checked on confirm
+    pub confirm_input: String,              // phrase typed into a protected confirmation
+    pub confirm_replay: bool,               // confirmation is for the selected replay
     
     // Logic/Flow state
     pub awaiting_log_prompt: bool,
     pub last_run_id: Option<u64>,
+    pub last_run_header: String, // "Run #.. | status: .. | conclusion: .." of the last fetched run
 
     // Search
     pub search_active: bool,
-    pub search_query: String,
+    pub search_query: TextInput,
     pub filtered_repo_indices: Vec<usize>,
+    pub repo_rows: Vec<RepoRow>, // what the Repos panel shows, built from the filtered repos
+    pub collapsed_groups: std::collections::HashSet<String>,
     pub filtered_branch_indices: Vec<usize>,
     pub filtered_workflow_indices: Vec<usize>,
 
     pub repos_hscroll: u16,
     pub output_scroll: u16,
+
+    pub hit_areas: HitAreas,
 }
 
 #[derive(Debug, Default)]
 pub struct AppState {
-    pub config: Config,
+    pub config: Config, // user layer merged over the team layer
+    pub config_paths: ConfigPaths,
+    config_stamps: (FileStamp, FileStamp), // user and team files as last loaded or saved
+    pub config_read_only: bool, // config failed to load; never overwrite it
     pub data: AppData,
     pub ui: UiState,
     pub github: GitHubService,
+    pub tracker: RunTracker,
+    pub hooks: HookRunner,
+    pub lookups: Lookups,
+    pub keymap: Keymap,
+    pub theme: Theme,
+    pub history: InputHistory,
+    history_path: PathBuf,
 }
 
 impl AppState {
-    pub fn new() -> Self {
-        // Load persisted repos from config
-        let config = load_config();
-        let repos: Vec<Repo> = config
-            .repos
-            .iter()
-            .map(|rc| Repo {
-                name: rc.name.clone(),
-                branches: vec![],
-                workflows: vec![],
-            })
-            .collect();
+    pub fn new(cli: &CliArgs) -> Self {
+        // Load persisted repos from config. A broken file is reported instead
+        // of silently treated as empty, and nothing is written back to it.
+        let config_paths = ConfigPaths::resolve(cli.config.as_deref(), cli.team_config.as_deref());
+        let (config, config_error, team_warning) = match load_effective_config(&config_paths) {
+            Ok((config, team_warning)) => (config, None, team_warning),
+            Err(e) => (Config::default(), Some(e.to_string()), None),
+        };
+        let dry_run = cli.dry_run_override().unwrap_or(config.dry_run);
+        let repos = repos_from_config(&config);
+        let config_stamps = config_paths.stamps();
+        let (keymap, keymap_warning) = keymap_from_config(&config);
+        let (theme, theme_warning) = theme_from_config(&config);
+        let history_path = config_paths.user.with_file_name(HISTORY_FILE);
+        let (history, history_warning) = match InputHistory::load(&history_path) {
+            Ok(history) => (history, None),
+            Err(e) => (InputHistory::default(), Some(format!("Input history ignored: {}", e))),
+        };
+        // List every warning, so a broken team file doesn't hide why the
+        // keymap or theme fell back to the defaults
+        let warnings: Vec<String> =
+            [team_warning, keymap_warning, theme_warning, history_warning].into_iter().flatten().collect();
 
         let branches: Vec<String> = vec![];
         let workflows: Vec<Workflow> = vec![];
@@ -122,10 +282,33 @@ impl AppState {
 
         let filtered_repo_indices: Vec<usize> = (0..repos.len()).collect();
         let has_repos = !repos.is_empty();
+        let output = if !warnings.is_empty() {
+            warnings.join("\n\n")
+        } else if has_repos {
+            format!(
+                "Ready to dispatch workflows...\n\nSelect a repo and press {} to load branches.\nPress '{}' to add a new repo, '{}' for all keybindings.",
+                keymap.label(Action::Select), keymap.label(Action::AddRepo), keymap.label(Action::Help)
+            )
+        } else {
+            format!(
+                "Welcome to Lazy-Dispatchrr!\n\nPress '{}' to add a repo, '{}' for all keybindings.",
+                keymap.label(Action::AddRepo), keymap.label(Action::Help)
+            )
+        };
 
-        Self {
+        let mut state = Self {
             config,
-            github: GitHubService::new(),
+            config_paths,
+            config_stamps,
+            config_read_only: config_error.is_some(),
+            github: GitHubService::with_dry_run(dry_run),
+            tracker: RunTracker::new(),
+            hooks: HookRunner::new(),
+            lookups: Lookups::new(),
+            keymap,
+            theme,
+            history,
+            history_path,
             data: AppData {
                 repos,
                 branches,
@@ -133,6 +316,12 @@ impl AppState {
                 inputs,
                 input_fields: vec![],
                 replays_list: vec![],
+                run_logs: vec![],
+                run_failures: vec![],
+                dry_run_hooks: vec![],
+                owner_logins: None,
+                repo_names: Default::default(),
+                dynamic_values: Default::default(),
             },
             ui: UiState {
                 repos_state,
@@ -140,45 +329,68 @@ impl AppState {
                 workflows_state,
                 inputs_state,
                 replays_state: ratatui::widgets::ListState::default(),
+                failures_state: ratatui::widgets::ListState::default(),
                 focus: Focus::Repo,
-                output: Some(if has_repos {
-                    "Ready to dispatch workflows...\n\nSelect a repo and press Enter to load branches.\nPress 'a' to add a new repo, '?' for all keybindings.".to_string()
-                } else {
-                    "Welcome to Lazy-Dispatchrr!\n\nPress 'a' to add a repo, '?' for all keybindings.".to_string()
-                }),
-                output_is_error: false,
+                output: Some(output),
+                output_is_error: !warnings.is_empty(),
                 output_is_success: false,
                 dispatch_output_lines: vec![],
                 show_add_repo_popup: false,
-                add_repo_owner: String::new(),
-                add_repo_name: String::new(),
+                add_repo_owner: TextInput::default(),
+                add_repo_name: TextInput::default(),
                 add_repo_focus_owner: true,
+                add_repo_suggestion: 0,
                 show_inputs_popup: false,
                 input_fields_selected: 0,
                 input_fields_editing: false,
                 show_confirm_dispatch: false,
                 dispatch_command_preview: String::new(),
+                dispatch_changes: vec![],
+                dispatch_guard: None,
+                dispatch_target: None,
+                confirm_input: String::new(),
+                confirm_replay: false,
                 show_help_popup: false,
                 awaiting_log_prompt: false,
                 last_run_id: None,
+                last_run_header: String::new(),
                 show_replays_popup: false,
+                show_failures_popup: false,
+                config_error,
+                confirm_remove_repo: None,
+                repo_edit: None,
+                repo_import: None,
+                command_palette: None,
                 search_active: false,
-                search_query: String::new(),
+                search_query: TextInput::default(),
                 filtered_repo_indices,
+                repo_rows: vec![],
+                collapsed_groups: Default::default(),
                 filtered_branch_indices: vec![],
                 filtered_workflow_indices: vec![],
                 repos_hscroll: 0,
                 output_scroll: 0,
+                input_editor: TextInput::default(),
+                input_suggestion: 0,
+                input_problems: vec![],
+                input_sort: InputSort::default(),
+                hit_areas: HitAreas::default(),
             },
-        }
+        };
+        state.rebuild_repo_rows();
+        state
     }
 
     // --- Fuzzy search helpers ---
 
-    /// Get the real index into `self.data.repos` for the currently selected filtered item.
+    /// Get the real index into `self.data.repos` for the currently selected
+    /// row. `None` when a group header is selected.
     pub fn selected_repo_real_index(&self) -> Option<usize> {
         let sel = self.ui.repos_state.selected()?;
-        self.ui.filtered_repo_indices.get(sel).copied()
+        match self.ui.repo_rows.get(sel)? {
+            RepoRow::Repo(i) => Some(*i),
+            RepoRow::Group { .. } => None,
+        }
     }
 
     /// Get the real index into `self.data.branches` for the currently selected filtered item.
@@ -196,7 +408,7 @@ impl AppState {
     /// Re-filter the currently focused list based on `self.ui.search_query`.
     pub fn update_search_filter(&mut self) {
         let matcher = SkimMatcherV2::default();
-        let query = &self.ui.search_query;
+        let query = self.ui.search_query.value();
 
         match self.ui.focus {
             Focus::Repo => {
@@ -208,13 +420,14 @@ impl AppState {
                         .iter()
                         .enumerate()
                         .filter_map(|(i, r)| {
-                            matcher.fuzzy_match(&r.name, query).map(|score| (i, score))
+                            matcher.fuzzy_match(&r.label(), query).map(|score| (i, score))
                         })
                         .collect();
-                    scored.sort_by(|a, b| b.1.cmp(&a.1));
+                    scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
                     self.ui.filtered_repo_indices = scored.into_iter().map(|(i, _)| i).collect();
                 }
-                self.ui.repos_state.select(if self.ui.filtered_repo_indices.is_empty() {
+                self.rebuild_repo_rows();
+                self.ui.repos_state.select(if self.ui.repo_rows.is_empty() {
                     None
                 } else {
                     Some(0)
@@ -232,7 +445,7 @@ impl AppState {
                             matcher.fuzzy_match(b, query).map(|score| (i, score))
                         })
                         .collect();
-                    scored.sort_by(|a, b| b.1.cmp(&a.1));
+                    scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
                     self.ui.filtered_branch_indices = scored.into_iter().map(|(i, _)| i).collect();
                 }
                 self.ui.branches_state.select(if self.ui.filtered_branch_indices.is_empty() {
@@ -253,7 +466,7 @@ impl AppState {
                             matcher.fuzzy_match(&w.name, query).map(|score| (i, score))
                         })
                         .collect();
-                    scored.sort_by(|a, b| b.1.cmp(&a.1));
+                    scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
                     self.ui.filtered_workflow_indices =
                         scored.into_iter().map(|(i, _)| i).collect();
                 }
@@ -272,6 +485,7 @@ impl AppState {
         self.ui.search_active = false;
         self.ui.search_query.clear();
         self.ui.filtered_repo_indices = (0..self.data.repos.len()).collect();
+        self.rebuild_repo_rows();
         self.ui.filtered_branch_indices = (0..self.data.branches.len()).collect();
         self.ui.filtered_workflow_indices = (0..self.data.workflows.len()).collect();
     }
@@ -285,11 +499,13 @@ impl AppState {
             name: format!("{}/{}", owner, name),
             branches,
             workflows,
+            ..Default::default()
         };
 
         self.ui.output = Some(format!("Added repo '{}'", repo.name));
+        let added = repo.name.clone();
         self.data.repos.push(repo);
-        self.ui.filtered_repo_indices = (0..self.data.repos.len()).collect();
+        self.refresh_repo_list(Some(&added));
 
         // Persist to config file
         self.save_repos_to_config()?;
@@ -297,26 +513,754 @@ impl AppState {
         Ok(())
     }
 
+    // --- Add Repo popup ---
+
+    /// Open the Add Repo popup, fetching owner suggestions the first time.
+    /// They show up once `gh` answers; until then the fields work without.
+    pub fn open_add_repo(&mut self) {
+        self.ui.show_add_repo_popup = true;
+        self.ui.add_repo_focus_owner = true;
+        self.ui.add_repo_suggestion = 0;
+        if self.data.owner_logins.is_none() {
+            self.data.owner_logins = Some(Vec::new());
+            self.lookups.owner_logins();
+        }
+    }
+
+    pub fn close_add_repo(&mut self) {
+        self.ui.show_add_repo_popup = false;
+        self.ui.add_repo_owner.clear();
+        self.ui.add_repo_name.clear();
+        self.ui.add_repo_focus_owner = true;
+        self.ui.add_repo_suggestion = 0;
+    }
+
+    /// Suggestions for the focused Add Repo field: prefix matches first,
+    /// then fuzzy matches. Repos already in the list are left out.
+    pub fn add_repo_suggestions(&self) -> Vec<String> {
+        const MAX_SUGGESTIONS: usize = 8;
+
+        let (candidates, typed): (Vec<&String>, &str) = if self.ui.add_repo_focus_owner {
+            (self.data.owner_logins.iter().flatten().collect(), self.ui.add_repo_owner.value())
+        } else {
+            let owner = self.ui.add_repo_owner.value().trim();
+            let names = self.data.repo_names.get(&owner.to_lowercase());
+            let unlisted = names.into_iter().flatten()
+                .filter(|name| !self.is_listed(&format!("{}/{}", owner, name)))
+                .collect();
+            (unlisted, self.ui.add_repo_name.value())
+        };
+
+        let typed_lower = typed.to_lowercase();
+        let mut suggestions: Vec<String> = candidates.iter()
+            .filter(|c| c.to_lowercase().starts_with(&typed_lower) && !c.eq_ignore_ascii_case(typed))
+            .map(|c| c.to_string())
+            .collect();
+        if !typed.is_empty() {
+            let matcher = SkimMatcherV2::default();
+            let mut fuzzy: Vec<(i64, &String)> = candidates.iter()
+                .filter(|c| !c.to_lowercase().starts_with(&typed_lower))
+                .filter_map(|c| matcher.fuzzy_match(c, typed).map(|score| (score, *c)))
+                .collect();
+            fuzzy.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+            suggestions.extend(fuzzy.into_iter().map(|(_, c)| c.clone()));
+        }
+        suggestions.truncate(MAX_SUGGESTIONS);
+        suggestions
+    }
+
+    /// Fill the focused field with the highlighted suggestion. Returns false
+    /// when there was nothing to accept.
+    pub fn accept_add_repo_suggestion(&mut self) -> bool {
+        let Some(suggestion) = self.add_repo_suggestions().into_iter().nth(self.ui.add_repo_suggestion) else {
+            return false;
+        };
+        if self.ui.add_repo_focus_owner {
+            self.ui.add_repo_owner.set_value(suggestion);
+            self.switch_add_repo_field();
+        } else {
+            self.ui.add_repo_name.set_value(suggestion);
+        }
+        self.ui.add_repo_suggestion = 0;
+        true
+    }
+
+    /// Move between the owner and repo fields, splitting a pasted reference
+    /// and loading repo name suggestions for the owner.
+    pub fn switch_add_repo_field(&mut self) {
+        self.split_pasted_repo_reference();
+        self.ui.add_repo_focus_owner = !self.ui.add_repo_focus_owner;
+        self.ui.add_repo_suggestion = 0;
+
+        let owner = self.ui.add_repo_owner.value().trim().to_lowercase();
+        if !self.ui.add_repo_focus_owner && !owner.is_empty() && !self.data.repo_names.contains_key(&owner) {
+            self.data.repo_names.insert(owner.clone(), Vec::new());
+            self.lookups.repo_names(owner);
+        }
+    }
+
+    /// Type into the focused field. A `/` after a plain owner name moves on
+    /// to the repo field, so `owner/repo` can be typed or pasted in one go.
+    pub fn type_into_add_repo(&mut self, c: char) {
+        self.ui.add_repo_suggestion = 0;
+        if self.ui.add_repo_focus_owner {
+            let owner = self.ui.add_repo_owner.value();
+            if c == '/' && !owner.is_empty() && owner.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                self.switch_add_repo_field();
+                return;
+            }
+            self.ui.add_repo_owner.insert(c);
+        } else {
+            self.ui.add_repo_name.insert(c);
+        }
+    }
+
+    /// If either field holds a full `owner/repo` or GitHub URL, split it
+    /// across both fields.
+    fn split_pasted_repo_reference(&mut self) {
+        let field = if self.ui.add_repo_focus_owner { &self.ui.add_repo_owner } else { &self.ui.add_repo_name };
+        if let Some((owner, name)) = parse_repo_reference(field.value()) {
+            self.ui.add_repo_owner.set_value(owner);
+            self.ui.add_repo_name.set_value(name);
+        }
+    }
+
+    /// What's wrong with the Add Repo fields as typed, shown under them.
+    pub fn add_repo_problem(&self) -> Option<String> {
+        let (owner, name) = (self.ui.add_repo_owner.value().trim(), self.ui.add_repo_name.value().trim());
+        if owner.is_empty() || name.is_empty() {
+            return None; // still typing
+        }
+        if self.is_listed(&format!("{}/{}", owner, name)) {
+            return Some(format!("{}/{} is already in your list", owner, name));
+        }
+        validate_repo_name(owner, name)
+    }
+
+    /// Validate the Add Repo fields and add the repo.
+    pub fn submit_add_repo(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.split_pasted_repo_reference();
+        let owner = self.ui.add_repo_owner.value().trim().to_string();
+        let name = self.ui.add_repo_name.value().trim().to_string();
+        if let Some(problem) = validate_repo_name(&owner, &name).or_else(|| self.add_repo_problem()) {
+            return Err(problem.into());
+        }
+        self.close_add_repo();
+        self.add_repo(&owner, &name)
+    }
+
+    /// Apply an editing key to the focused Add Repo field.
+    pub fn edit_add_repo_field(&mut self, key: &KeyEvent) {
+        let field = if self.ui.add_repo_focus_owner { &mut self.ui.add_repo_owner } else { &mut self.ui.add_repo_name };
+        if field.handle_key(key) {
+            self.ui.add_repo_suggestion = 0;
+        }
+    }
+
+    // --- Input fields ---
+
+    /// Start typing into the selected input field.
+    pub fn start_input_edit(&mut self) {
+        let value = self.data.input_fields.get(self.ui.input_fields_selected).map(|f| f.value.clone());
+        self.ui.input_editor = TextInput::new(value.unwrap_or_default());
+        self.ui.input_fields_editing = true;
+        self.ui.input_suggestion = 0;
+        self.load_dynamic_values();
+    }
+
+    /// Suggestions for the input being typed: values of its configured
+    /// source, values dispatched before, then recent tags or commits. Prefix matches come before other
+    /// matches; the current value itself is left out.
+    pub fn input_suggestions(&self) -> Vec<String> {
+        const MAX_SUGGESTIONS: usize = 8;
+
+        let Some(field) = self.data.input_fields.get(self.ui.input_fields_selected) else {
+            return vec![];
+        };
+        if !self.ui.input_fields_editing || matches!(field.input_type.as_str(), "choice" | "boolean") {
+            return vec![];
+        }
+        let mut candidates = if field.source.is_some() { field.options.clone() } else { Vec::new() };
+        if self.config.suggestions.history
+            && let (Some(repo), Some(workflow)) = (self.selected_repo_real_index(), self.selected_workflow_real_index()) {
+            let (repo, workflow) = (&self.data.repos[repo].name, &self.data.workflows[workflow].name);
+            let used = self.history.values(repo, workflow, &field.name, unix_now());
+            candidates.extend(used.into_iter().filter(|v| !candidates.contains(v)).collect::<Vec<_>>());
+        }
+        if let Some(key) = self.dynamic_values_key(&field.name)
+            && let Some(values) = self.data.dynamic_values.get(&key) {
+            candidates.extend(values.iter().filter(|v| !candidates.contains(v)).cloned().collect::<Vec<_>>());
+        }
+
+        let typed = field.value.to_lowercase();
+        let (mut prefixed, others): (Vec<String>, Vec<String>) = candidates
+            .into_iter()
+            .filter(|c| *c != field.value && c.to_lowercase().contains(&typed))
+            .partition(|c| c.to_lowercase().starts_with(&typed));
+        prefixed.extend(others);
+        prefixed.truncate(MAX_SUGGESTIONS);
+        prefixed
+    }
+
+    /// Replace the input being typed with the highlighted suggestion.
+    /// Returns false when there was nothing to accept.
+    pub fn accept_input_suggestion(&mut self) -> bool {
+        let Some(suggestion) = self.input_suggestions().into_iter().nth(self.ui.input_suggestion) else {
+            return false;
+        };
+        if let Some(field) = self.data.input_fields.get_mut(self.ui.input_fields_selected) {
+            field.set_value(suggestion.clone());
+        }
+        self.ui.input_editor.set_value(suggestion);
+        self.ui.input_suggestion = 0;
+        true
+    }
+
+    /// Cache key of the recent tags or commits an input can be filled with,
+    /// going by the words of its name: `version` and `tag` inputs (or
+    /// `image_tag`, `release-version`) take tags, `sha` and `commit` inputs
+    /// take commit SHAs of the selected branch.
+    fn dynamic_values_key(&self, input_name: &str) -> Option<String> {
+        if !self.config.suggestions.dynamic {
+            return None;
+        }
+        let name = input_name.to_lowercase();
+        let words: Vec<&str> = name.split(['_', '-']).collect();
+        let has = |word: &str| words.contains(&word);
+        let repo = &self.data.repos[self.selected_repo_real_index()?].name;
+        if has("sha") || has("commit") {
+            let branch = &self.data.branches[self.selected_branch_real_index()?];
+            Some(format!("commits:{}@{}", repo, branch))
+        } else if has("version") || has("tag") {
+            Some(format!("tags:{}", repo))
+        } else {
+            None
+        }
+    }
+
+    /// Fetch the recent tags or commits for the selected input once per
+    /// repo (or branch), in the background. Suggestions are a convenience,
+    /// so failures just leave them out.
+    fn load_dynamic_values(&mut self) {
+        let Some(field) = self.data.input_fields.get(self.ui.input_fields_selected) else {
+            return;
+        };
+        let Some(key) = self.dynamic_values_key(&field.name) else {
+            return;
+        };
+        if self.data.dynamic_values.contains_key(&key) {
+            return;
+        }
+        match key.split_once(':') {
+            Some(("commits", target)) => {
+                let (repo, branch) = target.split_once('@').unwrap_or((target, ""));
+                self.lookups.recent_commits(key.clone(), repo.to_string(), branch.to_string());
+            }
+            Some((_, repo)) => self.lookups.recent_tags(key.clone(), repo.to_string()),
+            None => {}
+        }
+        self.data.dynamic_values.insert(key, Vec::new());
+    }
+
+    /// Apply a key to the input field being edited: booleans flip on any
+    /// character, choices only change with Tab, anything else is free text.
+    pub fn edit_input_value(&mut self, key: &KeyEvent) {
+        let Some(field) = self.data.input_fields.get_mut(self.ui.input_fields_selected) else {
+            return;
+        };
+        match field.input_type.as_str() {
+            "choice" => {}
+            "boolean" => {
+                if matches!(key.code, KeyCode::Char(_)) {
+                    let flipped = if field.value == "true" { "false" } else { "true" };
+                    field.set_value(flipped);
+                }
+            }
+            _ => {
+                if self.ui.input_editor.handle_key(key) {
+                    field.set_value(self.ui.input_editor.value());
+                    self.ui.input_suggestion = 0;
+                }
+            }
+        }
+    }
+
+    /// The selected input's value as it should appear in `$EDITOR`: JSON
+    /// is pretty-printed, anything else is left as is.
+    pub fn input_editor_text(&self) -> Option<String> {
+        let field = self.data.input_fields.get(self.ui.input_fields_selected)?;
+        let pretty = serde_json::from_str::<serde_json::Value>(&field.value).ok()
+            .filter(|_| field.is_json())
+            .and_then(|json| serde_json::to_string_pretty(&json).ok());
+        Some(pretty.unwrap_or_else(|| field.value.clone()))
+    }
+
+    /// Store text written in `$EDITOR` into the selected input. Valid JSON is
+    /// compacted onto one line; invalid JSON is kept so it can be fixed.
+    pub fn set_input_from_editor(&mut self, text: &str) -> Result<(), String> {
+        let Some(field) = self.data.input_fields.get_mut(self.ui.input_fields_selected) else {
+            return Ok(());
+        };
+        // Editors add a final newline that isn't part of the value
+        let text = text.strip_suffix('\n').unwrap_or(text);
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        field.set_value(text.to_string());
+        if field.is_json()
+            && let Ok(json) = serde_json::from_str::<serde_json::Value>(text) {
+            field.set_value(json.to_string());
+        }
+        self.ui.input_editor = TextInput::new(field.value.clone());
+        match field.json_problem() {
+            Some(problem) => Err(format!("{}: {}", field.name, problem)),
+            None => Ok(()),
+        }
+    }
+
+    /// Insert bracketed-paste text into whichever text field is active.
+    pub fn paste(&mut self, text: &str) {
+        if self.ui.show_add_repo_popup {
+            let field = if self.ui.add_repo_focus_owner { &mut self.ui.add_repo_owner } else { &mut self.ui.add_repo_name };
+            field.insert_str(text.trim());
+            self.ui.add_repo_suggestion = 0;
+            // A pasted `owner/repo` or URL fills both fields right away
+            self.split_pasted_repo_reference();
+        } else if self.ui.show_inputs_popup && self.ui.input_fields_editing {
+            if let Some(field) = self.data.input_fields.get_mut(self.ui.input_fields_selected)
+                && !matches!(field.input_type.as_str(), "choice" | "boolean") {
+                self.ui.input_editor.insert_str(text);
+                field.set_value(self.ui.input_editor.value());
+            }
+        } else if self.ui.search_active {
+            self.ui.search_query.insert_str(text);
+            self.update_search_filter();
+        }
+    }
+
+    // --- Bulk import ---
+
+    /// Open the bulk-import popup at its owner prompt.
+    pub fn open_repo_import(&mut self) {
+        self.ui.repo_import = Some(RepoImport::default());
+    }
+
+    /// Fetch the repos of the owner typed into the import popup.
+    pub fn fetch_import_listings(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let import = self.ui.repo_import.as_mut().ok_or("Import is not open.")?;
+        let owner = import.owner.trim();
+        let listings = self.github.list_owner_repos(Some(owner).filter(|o| !o.is_empty()))?;
+        if listings.is_empty() {
+            return Err("No repositories found.".into());
+        }
+        import.listings = Some(listings);
+        import.filter.clear();
+        import.checked.clear();
+        self.update_import_filter();
+        Ok(())
+    }
+
+    /// Re-filter the import list on its fuzzy filter, best matches first.
+    pub fn update_import_filter(&mut self) {
+        let Some(import) = self.ui.repo_import.as_mut() else { return };
+        let Some(listings) = &import.listings else { return };
+        let matcher = SkimMatcherV2::default();
+        let mut scored: Vec<(usize, i64)> = listings
+            .iter()
+            .enumerate()
+            .filter_map(|(i, l)| {
+                if import.filter.is_empty() {
+                    return Some((i, 0));
+                }
+                matcher.fuzzy_match(&format!("{} {}", l.name, l.description), &import.filter).map(|score| (i, score))
+            })
+            .collect();
+        scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        import.filtered = scored.into_iter().map(|(i, _)| i).collect();
+        import.list_state.select(if import.filtered.is_empty() { None } else { Some(0) });
+    }
+
+    /// Whether a listing is already in the Repos panel.
+    pub fn is_listed(&self, name: &str) -> bool {
+        self.data.repos.iter().any(|r| r.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Check or uncheck the highlighted repo in the import list.
+    pub fn toggle_import_check(&mut self) {
+        let Some(import) = self.ui.repo_import.as_ref() else { return };
+        let Some(idx) = import.list_state.selected().and_then(|sel| import.filtered.get(sel)).copied() else { return };
+        let name = import.listings.as_ref().map(|l| l[idx].name.clone()).unwrap_or_default();
+        if self.is_listed(&name) {
+            return;
+        }
+        let Some(import) = self.ui.repo_import.as_mut() else { return };
+        if !import.checked.remove(&idx) {
+            import.checked.insert(idx);
+        }
+    }
+
+    /// Check every visible repo that isn't listed yet, or uncheck them all
+    /// if they already are.
+    pub fn toggle_import_check_all(&mut self) {
+        let Some(import) = self.ui.repo_import.as_ref() else { return };
+        let Some(listings) = &import.listings else { return };
+        let candidates: Vec<usize> = import.filtered.iter().copied()
+            .filter(|&i| !self.is_listed(&listings[i].name))
+            .collect();
+        let Some(import) = self.ui.repo_import.as_mut() else { return };
+        if candidates.iter().all(|i| import.checked.contains(i)) {
+            for i in &candidates {
+                import.checked.remove(i);
+            }
+        } else {
+            import.checked.extend(candidates);
+        }
+    }
+
+    /// Add every checked repo to the Repos panel and save them in one write.
+    pub fn import_checked_repos(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let import = self.ui.repo_import.take().ok_or("Import is not open.")?;
+        let listings = import.listings.unwrap_or_default();
+        let mut checked: Vec<usize> = import.checked.into_iter().collect();
+        checked.sort_unstable();
+        if checked.is_empty() {
+            return Err("No repos selected; press Space to select.".into());
+        }
+
+        let mut added = Vec::new();
+        for i in checked {
+            let name = &listings[i].name;
+            if !self.is_listed(name) {
+                self.data.repos.push(Repo { name: name.clone(), ..Default::default() });
+                added.push(name.clone());
+            }
+        }
+        self.refresh_repo_list(added.first().map(String::as_str));
+        self.save_repos_to_config()?;
+
+        self.ui.output = Some(format!("Imported {} repos:\n  {}", added.len(), added.join("\n  ")));
+        self.ui.output_is_error = false;
+        Ok(())
+    }
+
+    /// Re-read the config file before modifying it. Refused in read-only
+    /// mode so a file that failed to load is never overwritten.
+    fn reload_config(&self) -> Result<Config, ConfigError> {
+        if self.config_read_only {
+            return Err(ConfigError::ReadOnly);
+        }
+        load_config_from(&self.config_paths.user)
+    }
+
+    /// Write the config file unless running read-only. Refused when the file
+    /// was edited outside the app since it was last loaded, so those edits
+    /// are never clobbered; the next tick reloads it.
+    ///
+    /// The saved file is merged over the team layer into the effective
+    /// config right away, since the updated stamp keeps the next tick from
+    /// reloading it.
+    fn persist_config(&mut self, config: &Config) -> Result<(), ConfigError> {
+        if self.config_read_only {
+            return Err(ConfigError::ReadOnly);
+        }
+        let path = &self.config_paths.user;
+        if FileStamp::of(path) != self.config_stamps.0 {
+            return Err(ConfigError::Conflict { path: path.clone() });
+        }
+        save_config_to(path, config)?;
+        self.config_stamps.0 = FileStamp::of(path);
+
+        if let Ok((effective, _)) = load_effective_config(&self.config_paths) {
+            self.config = effective;
+        }
+        Ok(())
+    }
+
+    /// Try loading the config again after the user edited it from the
+    /// startup error screen.
+    pub fn retry_config_load(&mut self) {
+        match load_effective_config(&self.config_paths) {
+            Ok((config, _)) => {
+                self.config_stamps = self.config_paths.stamps();
+                self.ui.repos_state.select(None);
+                self.apply_config(config);
+                self.config_read_only = false;
+                self.ui.config_error = None;
+                self.ui.output = Some(format!("Config reloaded: {} repos.", self.data.repos.len()));
+                self.ui.output_is_error = false;
+            }
+            Err(e) => {
+                self.ui.config_error = Some(e.to_string());
+            }
+        }
+    }
+
+    /// Reload the config when the user or team file changed on disk, e.g.
+    /// edited by hand or updated by a `git pull`. Called every tick.
+    pub fn reload_changed_config(&mut self) {
+        if self.config_paths.stamps() == self.config_stamps {
+            return;
+        }
+        let result = load_effective_config(&self.config_paths);
+        self.config_stamps = self.config_paths.stamps();
+
+        if self.config_read_only || self.ui.config_error.is_some() {
+            // Broken at startup: pick the file up as soon as it's fixed
+            match result {
+                Ok(_) => self.retry_config_load(),
+                Err(e) if self.ui.config_error.is_some() => self.ui.config_error = Some(e.to_string()),
+                Err(_) => {}
+            }
+            return;
+        }
+
+        match result {
+            Ok((config, team_warning)) => {
+                self.apply_config(config);
+                self.push_output_notice("↻ Config changed on disk and was reloaded.".to_string(), OutputTone::Info);
+                if let Some(warning) = team_warning {
+                    self.push_output_notice(warning, OutputTone::Warning);
+                }
+            }
+            Err(e) => self.push_output_notice(
+                format!("Config changed on disk but could not be loaded, keeping the previous settings: {}", e),
+                OutputTone::Error,
+            ),
+        }
+    }
+
+    /// Replace the effective config, merging its repos into the Repos panel
+    /// without losing the current selection or already-loaded repo data.
+    fn apply_config(&mut self, config: Config) {
+        let selected = self.selected_repo_real_index().map(|i| self.data.repos[i].name.clone());
+
+        let mut previous = std::mem::take(&mut self.data.repos);
+        self.data.repos = config
+            .repos
+            .iter()
+            .map(|rc| {
+                let mut repo = repo_from_config(rc);
+                if let Some(i) = previous.iter().position(|r| r.name == rc.name) {
+                    let loaded = previous.swap_remove(i);
+                    repo.branches = loaded.branches;
+                    repo.workflows = loaded.workflows;
+                }
+                repo
+            })
+            .collect();
+        let (keymap, keymap_warning) = keymap_from_config(&config);
+        self.keymap = keymap;
+        if let Some(warning) = keymap_warning {
+            self.push_output_notice(warning, OutputTone::Warning);
+        }
+        let (theme, theme_warning) = theme_from_config(&config);
+        self.theme = theme;
+        if let Some(warning) = theme_warning {
+            self.push_output_notice(warning, OutputTone::Warning);
+        }
+        self.config = config;
+        self.refresh_repo_list(selected.as_deref());
+
+        // Keep an open replays popup in sync with the new file
+        if self.ui.show_replays_popup {
+            let repo_name = self.selected_repo_real_index().map(|i| self.data.repos[i].name.clone());
+            self.data.replays_list = self.config.repos.iter()
+                .find(|rc| Some(&rc.name) == repo_name.as_ref())
+                .map(|rc| rc.replays.clone())
+                .unwrap_or_default();
+            if self.data.replays_list.is_empty() {
+                self.ui.show_replays_popup = false;
+            } else {
+                let sel = self.ui.replays_state.selected().unwrap_or(0);
+                self.ui.replays_state.select(Some(sel.min(self.data.replays_list.len() - 1)));
+            }
+        }
+    }
+
+    /// Recompute the Repos panel after `data.repos` changed, keeping an
+    /// active repo search and selecting the repo named `select` if visible.
+    fn refresh_repo_list(&mut self, select: Option<&str>) {
+        let previous_row = self.ui.repos_state.selected();
+        if matches!(self.ui.focus, Focus::Repo) && !self.ui.search_query.is_empty() {
+            self.update_search_filter();
+        } else {
+            self.ui.filtered_repo_indices = (0..self.data.repos.len()).collect();
+            self.rebuild_repo_rows();
+        }
+
+        let position = select.and_then(|name| {
+            self.ui.repo_rows.iter().position(|row| matches!(row, RepoRow::Repo(i) if self.data.repos[*i].name == name))
+        });
+        let rows = self.ui.repo_rows.len();
+        self.ui.repos_state.select(match position {
+            Some(pos) => Some(pos),
+            None if rows == 0 => None,
+            None => Some(previous_row.unwrap_or(0).min(rows - 1)),
+        });
+    }
+
+    /// Build the Repos panel rows from the filtered repos: favorites first,
+    /// then ungrouped repos, then each group under a collapsible header.
+    /// While searching, matches are listed flat in score order.
+    fn rebuild_repo_rows(&mut self) {
+        let repos = &self.data.repos;
+        let indices = &self.ui.filtered_repo_indices;
+        if !self.ui.search_query.is_empty() {
+            self.ui.repo_rows = indices.iter().map(|&i| RepoRow::Repo(i)).collect();
+            return;
+        }
+
+        let mut rows: Vec<RepoRow> = indices.iter()
+            .filter(|&&i| repos[i].favorite)
+            .chain(indices.iter().filter(|&&i| !repos[i].favorite && repos[i].group.is_none()))
+            .map(|&i| RepoRow::Repo(i))
+            .collect();
+
+        let mut groups: Vec<&str> = Vec::new();
+        for &i in indices {
+            if let Some(group) = repos[i].group.as_deref()
+                && !repos[i].favorite
+                && !groups.contains(&group) {
+                groups.push(group);
+            }
+        }
+        for group in groups {
+            let members: Vec<usize> = indices.iter().copied()
+                .filter(|&i| !repos[i].favorite && repos[i].group.as_deref() == Some(group))
+                .collect();
+            let collapsed = self.ui.collapsed_groups.contains(group);
+            rows.push(RepoRow::Group { name: group.to_string(), count: members.len(), collapsed });
+            if !collapsed {
+                rows.extend(members.into_iter().map(RepoRow::Repo));
+            }
+        }
+        self.ui.repo_rows = rows;
+    }
+
+    /// Expand or collapse the selected group header. Returns false when the
+    /// selection is a repo.
+    pub fn toggle_selected_group(&mut self) -> bool {
+        let Some(RepoRow::Group { name, .. }) = self.ui.repos_state.selected().and_then(|sel| self.ui.repo_rows.get(sel)) else {
+            return false;
+        };
+        let name = name.clone();
+        if !self.ui.collapsed_groups.remove(&name) {
+            self.ui.collapsed_groups.insert(name);
+        }
+        self.rebuild_repo_rows();
+        true
+    }
+
+    /// Name of the selected repo, refusing repos that only exist in the team
+    /// config since changes to them could not be saved.
+    fn selected_user_repo(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let idx = self.selected_repo_real_index().ok_or("No repo selected.")?;
+        let name = self.data.repos[idx].name.clone();
+        let shared = self.config.repos.iter().any(|rc| rc.name == name && rc.source == ConfigSource::Team);
+        if shared {
+            return Err(format!("'{}' is shared from the team config; edit it there", name).into());
+        }
+        Ok(name)
+    }
+
+    /// Ask for confirmation before removing the selected repo.
+    pub fn request_remove_repo(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.ui.confirm_remove_repo = Some(self.selected_user_repo()?);
+        Ok(())
+    }
+
+    /// Remove the repo awaiting confirmation, along with its saved replays.
+    pub fn remove_repo(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let name = self.ui.confirm_remove_repo.take().ok_or("No repo to remove.")?;
+        self.data.repos.retain(|r| r.name != name);
+        self.refresh_repo_list(None);
+        self.save_repos_to_config()?;
+        self.ui.output = Some(format!("Removed repo '{}'.", name));
+        self.ui.output_is_error = false;
+        Ok(())
+    }
+
+    /// Swap the selected repo with its neighbour in the same section of the
+    /// Repos panel. Moving past the end of a section does nothing.
+    pub fn move_selected_repo(&mut self, down: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let name = self.selected_user_repo()?;
+        let sel = self.ui.repos_state.selected().unwrap_or(0);
+        let neighbour_row = if down { sel.checked_add(1) } else { sel.checked_sub(1) };
+        let Some(RepoRow::Repo(other)) = neighbour_row.and_then(|row| self.ui.repo_rows.get(row)) else {
+            return Ok(());
+        };
+        let Some(current) = self.selected_repo_real_index() else {
+            return Ok(());
+        };
+        let (a, b) = (&self.data.repos[current], &self.data.repos[*other]);
+        if a.favorite != b.favorite || (!a.favorite && a.group != b.group) {
+            return Ok(());
+        }
+
+        self.data.repos.swap(current, *other);
+        self.refresh_repo_list(Some(&name));
+        self.save_repos_to_config()
+    }
+
+    /// Pin the selected repo to the top of the list, or unpin it.
+    pub fn toggle_favorite(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let name = self.selected_user_repo()?;
+        if let Some(repo) = self.data.repos.iter_mut().find(|r| r.name == name) {
+            repo.favorite = !repo.favorite;
+        }
+        self.refresh_repo_list(Some(&name));
+        self.save_repos_to_config()
+    }
+
+    /// Open the rename popup for the selected repo's alias or group.
+    pub fn start_repo_edit(&mut self, field: RepoField) -> Result<(), Box<dyn std::error::Error>> {
+        let name = self.selected_user_repo()?;
+        let repo = self.data.repos.iter().find(|r| r.name == name).ok_or("No repo selected.")?;
+        let value = match field {
+            RepoField::Alias => repo.alias.clone(),
+            RepoField::Group => repo.group.clone(),
+        };
+        self.ui.repo_edit = Some(RepoEdit { repo: name, field, value: value.unwrap_or_default() });
+        Ok(())
+    }
+
+    /// Apply the rename popup. An empty value clears the alias or group.
+    pub fn finish_repo_edit(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let edit = self.ui.repo_edit.take().ok_or("Nothing to edit.")?;
+        let value = Some(edit.value.trim().to_string()).filter(|v| !v.is_empty());
+        if let Some(repo) = self.data.repos.iter_mut().find(|r| r.name == edit.repo) {
+            match edit.field {
+                RepoField::Alias => repo.alias = value,
+                RepoField::Group => repo.group = value,
+            }
+        }
+        self.refresh_repo_list(Some(&edit.repo));
+        self.save_repos_to_config()
+    }
+
     /// Save current repos list to the config file, preserving replays.
-    fn save_repos_to_config(&self) -> Result<(), Box<dyn std::error::Error>> {
+    fn save_repos_to_config(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         // Load existing config to preserve replays for repos we didn't touch
-        let mut existing = load_config();
+        let mut existing = self.reload_config()?;
         let mut repo_configs: Vec<RepoConfig> = Vec::new();
         for repo in &self.data.repos {
             // Find existing replays for this repo
-            let replays = existing
-                .repos
-                .iter()
-                .find(|rc| rc.name == repo.name)
-                .map(|rc| rc.replays.clone())
-                .unwrap_or_default();
+            let existing_repo = existing.repos.iter().find(|rc| rc.name == repo.name);
+            // Repos that only come from the team file stay there
+            let team_only = self.config.repos.iter()
+                .any(|rc| rc.name == repo.name && rc.source == ConfigSource::Team);
+            if existing_repo.is_none() && team_only {
+                continue;
+            }
             repo_configs.push(RepoConfig {
                 name: repo.name.clone(),
-                replays,
+                alias: repo.alias.clone(),
+                group: repo.group.clone(),
+                favorite: repo.favorite,
+                replays: existing_repo.map(|rc| rc.replays.clone()).unwrap_or_default(),
+                source: ConfigSource::User,
             });
         }
         existing.repos = repo_configs;
-        save_config(&existing)?;
+        self.persist_config(&existing)?;
         Ok(())
     }
 
@@ -438,27 +1382,64 @@ impl AppState {
         let branch_ref = self.selected_branch_real_index()
             .map(|idx| self.data.branches[idx].clone());
 
-        let (inputs_list, fields) = self.github.fetch_workflow_inputs(repo_name, workflow_filename, branch_ref.as_deref())?;
+        let (inputs_list, mut fields) = self.github.fetch_workflow_inputs(repo_name, workflow_filename, branch_ref.as_deref())?;
+
+        // Fill free-text inputs from their configured sources; a failing
+        // source leaves the input as plain text.
+        let source_ctx = sources::SourceContext {
+            repo: repo_name,
+            git_ref: branch_ref.as_deref().unwrap_or_default(),
+            workflow: workflow_filename,
+            branches: &self.data.branches,
+        };
+        let mut source_problems = Vec::new();
+        for field in fields.iter_mut().filter(|f| f.input_type != "choice" && f.input_type != "boolean") {
+            let Some(source) = sources::find(&self.config.input_sources, &source_ctx, &field.name) else {
+                continue;
+            };
+            match sources::resolve(source, &source_ctx, &field.name, &self.github) {
+                Ok(values) if !values.is_empty() => {
+                    field.options = values;
+                    field.source = Some(sources::describe(source));
+                }
+                Ok(_) => source_problems.push(format!("- {}: {} returned no values", field.name, sources::describe(source))),
+                Err(e) => source_problems.push(format!("- {}: {} failed: {}", field.name, sources::describe(source), e)),
+            }
+        }
+
+        let rule_problems = input_rules::apply(&mut fields, &self.config.input_rules, repo_name, workflow_filename);
+        input_rules::sort(&mut fields, self.ui.input_sort);
 
         self.data.inputs = inputs_list;
         self.data.input_fields = fields;
+        self.ui.input_problems.clear();
         self.ui.input_fields_selected = 0;
         self.ui.input_fields_editing = false;
 
         if self.data.inputs.is_empty() {
             self.ui.inputs_state.select(None);
             self.ui.output = Some(format!(
-                "Workflow '{}' has no dispatch inputs.\n\nPress 'i' or Enter to dispatch.",
-                workflow_filename
+                "Workflow '{}' has no dispatch inputs.\n\nPress '{}' or {} to dispatch.",
+                workflow_filename,
+                self.keymap.label(Action::EditInputs),
+                self.keymap.label(Action::Select)
             ));
         } else {
             self.ui.inputs_state.select(Some(0));
             let display: Vec<String> = self.data.inputs.iter().map(|i| format!("- {}", i)).collect();
-            self.ui.output = Some(format!(
-                "Inputs for '{}':\n\n{}\n\nPress 'i' to edit inputs and dispatch.",
+            let mut output = format!(
+                "Inputs for '{}':\n\n{}\n\nPress '{}' to edit inputs and dispatch.",
                 workflow_filename,
-                display.join("\n")
-            ));
+                display.join("\n"),
+                self.keymap.label(Action::EditInputs)
+            );
+            if !source_problems.is_empty() {
+                output.push_str(&format!("\n\nInput sources unavailable, using free text:\n{}", source_problems.join("\n")));
+            }
+            if !rule_problems.is_empty() {
+                output.push_str(&format!("\n\nInput tags ignored:\n{}", rule_problems.join("\n")));
+            }
+            self.ui.output = Some(output);
         }
         Ok(())
     }
@@ -482,35 +1463,60 @@ impl AppState {
         let workflow_filename = &self.data.workflows[selected_wf_idx].name;
 
         let (_, preview) = self.github.dispatch_workflow(repo_name, &selected_branch, workflow_filename, &self.data.input_fields)?;
+        let sent_inputs = dispatch_inputs(&self.data.input_fields);
+        self.on_dispatched(TrackedRun {
+            repo: repo_name.clone(),
+            workflow: workflow_filename.clone(),
+            branch: selected_branch.clone(),
+            inputs: sent_inputs,
+            dispatched_at: unix_now(),
+        });
 
         self.ui.output_is_success = true;
         self.ui.output_is_error = false;
 
         let inputs_display = self.data.input_fields
             .iter()
-            .map(|f| format!("  {} = {}", f.name, f.value))
+            .map(|f| match (f.is_set(), f.value.is_empty()) {
+                _ if !f.is_shown(&self.data.input_fields) => format!("  {} (hidden, not sent)", f.name),
+                (true, false) => format!("  {} = {}", f.name, f.value),
+                (true, true) => format!("  {} = \"\"", f.name),
+                (false, false) => format!("  {} = {} (workflow default, not sent)", f.name, f.value),
+                (false, true) => format!("  {} (unset)", f.name),
+            })
             .collect::<Vec<_>>()
             .join("\n");
 
+        let (headline, command_label) = if self.github.is_dry_run() {
+            ("DRY RUN — workflow not dispatched.", "Would have run:")
+        } else {
+            ("✓ Workflow dispatched!", "Command:")
+        };
         self.ui.dispatch_output_lines = vec![
-            ("✓ Workflow dispatched!".to_string(), DispatchOutputColor::Green),
-            (String::new(), DispatchOutputColor::White),
-            ("Command:".to_string(), DispatchOutputColor::Yellow),
-            (format!("  {}", preview), DispatchOutputColor::Yellow),
-            (String::new(), DispatchOutputColor::White),
-            ("Inputs:".to_string(), DispatchOutputColor::White),
+            (headline.to_string(), OutputTone::Success),
+            (String::new(), OutputTone::Normal),
+            (command_label.to_string(), OutputTone::Warning),
+            (format!("  {}", preview), OutputTone::Warning),
+            (String::new(), OutputTone::Normal),
+            ("Inputs:".to_string(), OutputTone::Normal),
         ];
         for line in inputs_display.lines() {
-            self.ui.dispatch_output_lines.push((line.to_string(), DispatchOutputColor::White));
+            self.ui.dispatch_output_lines.push((line.to_string(), OutputTone::Normal));
+        }
+        self.ui.dispatch_output_lines.push((String::new(), OutputTone::Normal));
+        if self.github.is_dry_run() {
+            for line in self.dry_run_log().lines() {
+                self.ui.dispatch_output_lines.push((line.to_string(), OutputTone::Info));
+            }
+        } else {
+            self.ui.dispatch_output_lines.push((
+                self.log_prompt_hint("watch run logs", "continue"),
+                OutputTone::Info,
+            ));
         }
-        self.ui.dispatch_output_lines.push((String::new(), DispatchOutputColor::White));
-        self.ui.dispatch_output_lines.push((
-            "Press 'l' to watch run logs, 'v' to open in browser, or any other key to continue.".to_string(),
-            DispatchOutputColor::Blue,
-        ));
 
         self.ui.output = Some("dispatch_success".to_string());
-        self.ui.awaiting_log_prompt = true;
+        self.ui.awaiting_log_prompt = !self.github.is_dry_run();
         Ok(())
     }
 
@@ -520,7 +1526,7 @@ impl AppState {
             Some(idx) => idx,
             None => return Err("No repo selected.".into()),
         };
-        let repo_name = &self.data.repos[selected_repo_idx].name;
+        let repo_name = self.data.repos[selected_repo_idx].name.clone();
 
         let selected_wf_idx = match self.selected_workflow_real_index() {
             Some(idx) => idx,
@@ -531,15 +1537,383 @@ impl AppState {
         self.ui.output = Some(format!("Fetching latest run for '{}'...", workflow_filename));
         self.ui.output_is_error = false;
 
-        let (run_id, status, conclusion, logs) = self.github.get_latest_run_logs(repo_name, workflow_filename)?;
+        let (run_id, status, conclusion, logs) = self.github.get_latest_run_logs(&repo_name, workflow_filename)?;
         self.ui.last_run_id = Some(run_id);
+        self.ui.last_run_header = format!("Run #{} | status: {} | conclusion: {}", run_id, status, conclusion);
+        self.data.run_logs = logs.lines().map(String::from).collect();
 
-        self.ui.output = Some(format!(
-            "Run #{} | status: {} | conclusion: {}\n{}\n\n{}\n\nPress 'l' to refresh logs, 'v' to open in browser, or any other key to dismiss.",
-            run_id, status, conclusion,
-            "─".repeat(60),
-            logs
-        ));
+        self.data.run_failures = if conclusion == "failure" {
+            self.collect_run_failures(&repo_name, run_id)
+        } else {
+            vec![]
+        };
+        self.ui.failures_state.select(if self.data.run_failures.is_empty() { None } else { Some(0) });
+
+        self.show_run_log(None);
+        Ok(())
+    }
+
+    /// Combine check-run annotations with error markers scanned from the log,
+    /// dropping log entries that only repeat an annotation.
+    fn collect_run_failures(&self, repo_name: &str, run_id: u64) -> Vec<RunFailure> {
+        // Annotations are a best-effort extra; the log scan still works without them
+        let mut failures = self.github.fetch_run_failures(repo_name, run_id).unwrap_or_default();
+
+        // Point annotations at the first matching line of their job's log
+        for failure in &mut failures {
+            let needle = failure.message.lines().next().unwrap_or("");
+            failure.log_line = self.data.run_logs.iter().position(|line| {
+                line.starts_with(&failure.job) && !needle.is_empty() && line.contains(needle)
+            });
+        }
+
+        for found in extract_log_errors(&self.data.run_logs) {
+            let duplicate = failures.iter_mut().find(|f| {
+                f.job == found.job && (f.message.contains(&found.message) || found.message.contains(&f.message))
+            });
+            match duplicate {
+                Some(existing) => {
+                    if existing.log_line.is_none() {
+                        existing.log_line = found.log_line;
+                    }
+                    if existing.step.is_none() {
+                        existing.step = found.step;
+                    }
+                }
+                None => failures.push(found),
+            }
+        }
+
+        failures
+    }
+
+    /// Render the last fetched run into the Output panel: header, failure
+    /// summary, then either the log tail or the lines around `focus_line`.
+    fn show_run_log(&mut self, focus_line: Option<usize>) {
+        let total = self.data.run_logs.len();
+        let (start, end) = match focus_line {
+            Some(line) => {
+                let start = line.saturating_sub(LOG_CONTEXT_LINES);
+                (start, (start + LOG_TAIL_LINES).min(total))
+            }
+            None => (total.saturating_sub(LOG_TAIL_LINES), total),
+        };
+
+        let mut text = format!("{}\n{}\n", self.ui.last_run_header, "─".repeat(60));
+
+        if !self.data.run_failures.is_empty() {
+            text.push_str("\nWhy it failed:\n");
+            for failure in &self.data.run_failures {
+                text.push_str(&format!("  ✗ {}\n", failure_label(failure)));
+            }
+            text.push_str(&format!(
+                "\nPress '{}' to browse failures and jump to them in the log.\n",
+                self.keymap.label(Action::Failures)
+            ));
+            text.push_str(&format!("{}\n", "─".repeat(60)));
+        }
+
+        if focus_line.is_some() {
+            text.push_str(&format!("\n(log lines {}-{} of {})\n", start + 1, end, total));
+        }
+        text.push('\n');
+        for (idx, line) in self.data.run_logs[start..end].iter().enumerate() {
+            let marker = if Some(start + idx) == focus_line { ">> " } else { "" };
+            text.push_str(marker);
+            text.push_str(line);
+            text.push('\n');
+        }
+
+        text.push_str(&format!("\n{}", self.log_prompt_hint("refresh logs", "dismiss")));
+        self.ui.output = Some(text);
+        self.ui.output_is_error = false;
+        self.ui.output_is_success = false;
+        self.ui.output_scroll = 0;
+    }
+
+    /// Open the failures popup for the last fetched run.
+    pub fn open_failures(&mut self) {
+        if self.data.run_failures.is_empty() {
+            self.ui.output = Some("No failures recorded for the last fetched run.".to_string());
+            self.ui.output_is_error = false;
+            return;
+        }
+        self.ui.show_failures_popup = true;
+        if self.ui.failures_state.selected().is_none() {
+            self.ui.failures_state.select(Some(0));
+        }
+    }
+
+    /// Show the log around the selected failure and close the popup.
+    pub fn jump_to_failure(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let idx = self.ui.failures_state.selected()
+            .ok_or("No failure selected.")?;
+        let failure = self.data.run_failures.get(idx)
+            .ok_or("No failure selected.")?;
+        let line = failure.log_line
+            .ok_or_else(|| format!("No log context found for '{}'.", failure.message))?;
+
+        self.ui.show_failures_popup = false;
+        self.ui.focus = Focus::Output;
+        self.show_run_log(Some(line));
+        Ok(())
+    }
+
+    // --- Run tracking ---
+
+    /// Start tracking a freshly dispatched run and fire its dispatch hooks.
+    /// A dry run has no run to track; its hooks are only recorded.
+    fn on_dispatched(&mut self, run: TrackedRun) {
+        let ctx = hook_context(&run, HookEvent::Dispatch, "", "");
+        if self.github.is_dry_run() {
+            self.data.dry_run_hooks.extend(
+                matching_hooks(&self.config.hooks, &ctx)
+                    .into_iter()
+                    .map(|hook| format!("hook {} ({} {})", hook, run.repo, run.workflow)),
+            );
+            return;
+        }
+        self.hooks.fire(&self.config.hooks, &ctx);
+        if self.config.suggestions.history {
+            self.history.record(&run.repo, &run.workflow, &run.inputs, run.dispatched_at);
+            if let Err(e) = self.history.save(&self.history_path) {
+                self.push_output_notice(format!("Could not save input history: {}", e), OutputTone::Warning);
+            }
+        }
+        self.tracker.track(run);
+    }
+
+    /// Everything a dry run has recorded so far, for the Output panel.
+    fn dry_run_log(&self) -> String {
+        let calls = self.github.recorded_calls();
+        let mut lines = vec![format!(
+            "Dry-run log ({} recorded call{}):",
+            calls.len() + self.data.dry_run_hooks.len(),
+            if calls.len() + self.data.dry_run_hooks.len() == 1 { "" } else { "s" }
+        )];
+        lines.extend(calls.iter().map(|c| format!("  {}", c)));
+        lines.extend(self.data.dry_run_hooks.iter().map(|h| format!("  {}", h)));
+        lines.join("\n")
+    }
+
+    /// Drain events from the run tracker, hook runner and lookups: remember
+    /// run ids, report progress in the Output panel, send notifications, fire
+    /// conclusion hooks when a run concludes and store fetched suggestions.
+    pub fn process_background_events(&mut self) {
+        while let Some(lookup) = self.lookups.try_recv() {
+            match lookup {
+                Lookup::OwnerLogins(logins) => self.data.owner_logins = Some(logins),
+                Lookup::RepoNames { owner, names } => {
+                    self.data.repo_names.insert(owner, names);
+                }
+                Lookup::DynamicValues { key, values } => {
+                    self.data.dynamic_values.insert(key, values);
+                }
+            }
+        }
+
+        while let Some(outcome) = self.hooks.try_recv() {
+            if let Err(e) = outcome.result {
+                self.push_output_notice(format!("Hook '{}' failed: {}", outcome.hook, e), OutputTone::Error);
+            }
+        }
+
+        while let Some(event) = self.tracker.try_recv() {
+            match event {
+                TrackerEvent::Started { run, run_id, url } => {
+                    self.ui.last_run_id = Some(run_id);
+                    self.push_output_notice(
+                        format!("▶ Run #{} started for {} ({}): {}", run_id, run.workflow, run.repo, url),
+                        OutputTone::Info,
+                    );
+                }
+                TrackerEvent::Concluded { run, run_id, conclusion, url } => {
+                    let color = match conclusion.as_str() {
+                        "success" => OutputTone::Success,
+                        "failure" | "timed_out" | "startup_failure" => OutputTone::Error,
+                        _ => OutputTone::Warning,
+                    };
+                    let message = format!("{} on {} concluded: {} — {}", run.workflow, run.repo, conclusion, url);
+                    self.push_output_notice(format!("■ Run #{} {}", run_id, message), color);
+                    if let Err(e) = crate::notify::notify(&self.config.notifications, "Lazy-Dispatchrr", &message) {
+                        self.push_output_notice(format!("Notification failed: {}", e), OutputTone::Error);
+                    }
+                    self.hooks.fire(&self.config.hooks, &hook_context(&run, HookEvent::Conclusion, &url, &conclusion));
+                }
+                TrackerEvent::Failed { run, error } => {
+                    self.push_output_notice(
+                        format!("Stopped tracking {} on {}: {}", run.workflow, run.repo, error),
+                        OutputTone::Error,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Append a line to whatever the Output panel currently shows, without
+    /// replacing it.
+    /// The hint shown while the post-dispatch log prompt is active, with the
+    /// configured keys, e.g. "Press 'l' to watch run logs, 'v' to open in
+    /// browser, or any other key to continue."
+    pub fn log_prompt_hint(&self, watch: &str, otherwise: &str) -> String {
+        format!(
+            "Press '{}' to {}, '{}' to open in browser, or any other key to {}.",
+            self.keymap.label(Action::WatchLogs),
+            watch,
+            self.keymap.label(Action::OpenInBrowser),
+            otherwise
+        )
+    }
+
+    pub fn push_output_notice(&mut self, line: String, tone: OutputTone) {
+        if self.ui.output_is_success && !self.ui.dispatch_output_lines.is_empty() {
+            self.ui.dispatch_output_lines.push((line, tone));
+        } else {
+            let current = self.ui.output.take().unwrap_or_default();
+            self.ui.output = Some(if current.is_empty() { line } else { format!("{}\n\n{}", current, line) });
+        }
+    }
+
+    // --- Command palette ---
+
+    /// Open the command palette with the commands that apply to the current
+    /// selection.
+    pub fn open_command_palette(&mut self) {
+        let repo = self.selected_repo_real_index();
+        let workflow = self.selected_workflow_real_index().map(|i| self.data.workflows[i].name.clone());
+        let branch_selected = self.selected_branch_real_index().is_some();
+
+        let keymap = &self.keymap;
+        let keys = |action| (!keymap.keys(action).is_empty()).then(|| keymap.labels(action));
+        let mut entries: Vec<PaletteEntry> = Vec::new();
+        let mut push = |command, label: String, keys: Option<String>| entries.push(PaletteEntry { command, label, keys });
+
+        if let Some(workflow) = &workflow {
+            push(PaletteCommand::Action(Action::Dispatch), format!("Dispatch {}", workflow), keys(Action::Dispatch));
+        }
+        for &action in PALETTE_ACTIONS {
+            let available = match action {
+                Action::EditInputs | Action::WatchLogs => workflow.is_some(),
+                Action::Failures => !self.data.run_failures.is_empty(),
+                Action::AddRepo | Action::ImportRepos | Action::Help | Action::Quit => true,
+                _ => repo.is_some(),
+            };
+            if available {
+                push(PaletteCommand::Action(action), action.description().to_string(), keys(action));
+            }
+        }
+        if let Some(repo) = repo {
+            push(PaletteCommand::Refresh, format!("Refresh branches and workflows of {}", self.data.repos[repo].name), None);
+            if let Some(workflow) = &workflow
+                && branch_selected {
+                push(PaletteCommand::ViewRuns, format!("View recent runs of {}", workflow), None);
+            }
+            let replays = self.config.repos.iter()
+                .find(|rc| rc.name == self.data.repos[repo].name)
+                .map(|rc| rc.replays.as_slice())
+                .unwrap_or_default();
+            for replay in replays {
+                push(PaletteCommand::Replay(Box::new(replay.clone())), format!("Replay {} ⟶ {}", replay.workflow, replay.description), None);
+            }
+        }
+        for (i, r) in self.data.repos.iter().enumerate() {
+            if Some(i) != repo {
+                push(PaletteCommand::SwitchRepo(i), format!("Switch to {}", r.label()), None);
+            }
+        }
+
+        let mut palette = CommandPalette {
+            filtered: (0..entries.len()).collect(),
+            entries,
+            ..Default::default()
+        };
+        palette.list_state.select(Some(0));
+        self.ui.command_palette = Some(palette);
+    }
+
+    /// Re-filter the palette on its query, best matches first.
+    pub fn update_palette_filter(&mut self) {
+        let Some(palette) = self.ui.command_palette.as_mut() else { return };
+        let matcher = SkimMatcherV2::default();
+        let mut scored: Vec<(usize, i64)> = palette
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, e)| {
+                if palette.query.is_empty() {
+                    return Some((i, 0));
+                }
+                matcher.fuzzy_match(&e.label, &palette.query).map(|score| (i, score))
+            })
+            .collect();
+        scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        palette.filtered = scored.into_iter().map(|(i, _)| i).collect();
+        palette.list_state.select(if palette.filtered.is_empty() { None } else { Some(0) });
+    }
+
+    /// Close the palette and return the highlighted command, if any.
+    pub fn take_palette_command(&mut self) -> Option<PaletteCommand> {
+        let palette = self.ui.command_palette.take()?;
+        let selected = palette.list_state.selected()?;
+        palette.filtered.get(selected).map(|&i| palette.entries[i].command.clone())
+    }
+
+    /// Select a repo in the Repos panel, clearing a search or expanding a
+    /// group that hides it, and load its branches.
+    pub fn switch_to_repo(&mut self, idx: usize) -> Result<(), Box<dyn std::error::Error>> {
+        let repo = self.data.repos.get(idx).ok_or("Repo no longer exists.")?;
+        let name = repo.name.clone();
+        if let Some(group) = &repo.group {
+            self.ui.collapsed_groups.remove(group);
+        }
+        self.reset_search();
+        self.refresh_repo_list(Some(&name));
+        self.ui.focus = Focus::Branches;
+        self.load_branches()
+    }
+
+    /// Run one of the selected repo's replays, found again in the freshly
+    /// loaded list so an edited or deleted replay is never mistaken for
+    /// another.
+    pub fn request_replay_of(&mut self, replay: &ReplayConfig) -> Result<(), Box<dyn std::error::Error>> {
+        self.open_replays();
+        if !self.ui.show_replays_popup {
+            return Ok(()); // no replays; `open_replays` explained why
+        }
+        let Some(idx) = self.data.replays_list.iter().position(|r| r == replay) else {
+            self.ui.show_replays_popup = false;
+            return Err("Replay no longer exists.".into());
+        };
+        self.ui.replays_state.select(Some(idx));
+        let result = self.request_replay();
+        if result.is_err() {
+            self.ui.show_replays_popup = false;
+        }
+        result
+    }
+
+    /// List the latest dispatched runs of the selected workflow and branch.
+    pub fn view_recent_runs(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let repo_idx = self.selected_repo_real_index().ok_or("No repo selected.")?;
+        let branch_idx = self.selected_branch_real_index().ok_or("No branch selected.")?;
+        let workflow_idx = self.selected_workflow_real_index().ok_or("No workflow selected.")?;
+        let repo_name = self.data.repos[repo_idx].name.clone();
+        let branch = self.data.branches[branch_idx].clone();
+        let workflow = self.data.workflows[workflow_idx].name.clone();
+
+        let runs = self.github.list_dispatched_runs(&repo_name, &workflow, &branch, None)?;
+        let mut text = format!("Recent dispatched runs of {} on {}:\n\n", workflow, branch);
+        if runs.is_empty() {
+            text.push_str("  (none)\n");
+        }
+        for run in &runs {
+            let outcome = if run.conclusion.is_empty() { &run.status } else { &run.conclusion };
+            text.push_str(&format!("  #{}  {:<12} {}\n      {}\n", run.id, outcome, run.created_at, run.url));
+        }
+        self.ui.output = Some(text);
+        self.ui.output_is_error = false;
+        self.ui.output_is_success = false;
+        self.ui.output_scroll = 0;
         Ok(())
     }
 
@@ -549,17 +1923,17 @@ impl AppState {
     pub fn save_replay(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         let selected_repo_idx = self.selected_repo_real_index()
             .ok_or("No repo selected.")?;
-        let repo_name = &self.data.repos[selected_repo_idx].name;
+        let repo_name = self.data.repos[selected_repo_idx].name.clone();
 
         let selected_wf_idx = self.selected_workflow_real_index()
             .ok_or("No workflow selected.")?;
         let workflow_filename = self.data.workflows[selected_wf_idx].name.clone();
 
-        // Only save if there are inputs with non-default values
+        // Save set inputs, even empty ones, and the defaults of the rest
         let inputs_with_values: Vec<ReplayInput> = self.data
             .input_fields
             .iter()
-            .filter(|f| !f.value.is_empty())
+            .filter(|f| (f.is_set() || !f.value.is_empty()) && f.is_shown(&self.data.input_fields))
             .map(|f| ReplayInput {
                 name: f.name.clone(),
                 value: f.value.clone(),
@@ -581,20 +1955,25 @@ impl AppState {
             workflow: workflow_filename.clone(),
             description,
             inputs: inputs_with_values,
+            source: ConfigSource::User,
         };
 
         // Load config, find this repo, add the replay
-        let mut config = load_config();
-        if let Some(rc) = config.repos.iter_mut().find(|rc| rc.name == *repo_name) {
+        let mut config = self.reload_config()?;
+        if let Some(rc) = config.repos.iter_mut().find(|rc| rc.name == repo_name) {
             rc.replays.push(replay.clone());
         } else {
             // Repo not in config yet (shouldn't happen, but handle gracefully)
             config.repos.push(RepoConfig {
                 name: repo_name.clone(),
+                alias: None,
+                group: None,
+                favorite: false,
                 replays: vec![replay.clone()],
+                source: ConfigSource::User,
             });
         }
-        save_config(&config)?;
+        self.persist_config(&config)?;
 
         self.ui.output = Some(format!(
             "✓ Replay saved for '{}' → {}\n  {}",
@@ -615,7 +1994,14 @@ impl AppState {
             }
         };
 
-        let config = load_config();
+        let config = match load_effective_config(&self.config_paths) {
+            Ok((config, _)) => config,
+            Err(e) => {
+                self.ui.output = Some(format!("Error loading replays: {}", e));
+                self.ui.output_is_error = true;
+                return;
+            }
+        };
         self.data.replays_list = config
             .repos
             .iter()
@@ -633,8 +2019,9 @@ impl AppState {
         self.ui.replays_state.select(Some(0));
     }
 
-    /// Run the selected replay with the currently selected branch.
-    pub fn run_replay(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    /// Run the selected replay, or ask for a typed confirmation first when a
+    /// guardrail protects it.
+    pub fn request_replay(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         let replay_idx = self.ui.replays_state.selected()
             .ok_or("No replay selected.")?;
         let replay = self.data.replays_list[replay_idx].clone();
@@ -648,45 +2035,100 @@ impl AppState {
             None => return Err("No branch selected.".into()),
         };
 
-        let mut args = vec![
-            "workflow".to_string(),
-            "run".to_string(),
-            replay.workflow.clone(),
-            "--repo".to_string(),
-            repo_name.clone(),
-            "--ref".to_string(),
-            selected_branch,
-        ];
-
-        for input in &replay.inputs {
-            args.push("-f".to_string());
-            args.push(format!("{}={}", input.name, input.value));
+        // Check against the workflow as it is on this branch now; when its
+        // inputs can't be fetched only GitHub's limits are checked
+        let fields = self.replay_fields(&repo_name, &replay.workflow, &selected_branch);
+        let inputs = replay_inputs(&replay, fields.as_deref());
+        let problems = validation::check(&inputs, fields.as_deref(), &self.config.limits);
+        if !problems.is_empty() {
+            let list: Vec<String> = problems.iter().map(|p| format!("- {}", p)).collect();
+            return Err(format!("GitHub would reject this replay's inputs:\n\n{}", list.join("\n")).into());
         }
 
-        let preview = format!("gh {}", args.join(" "));
+        let target = DispatchTarget {
+            repo: repo_name.clone(),
+            workflow: replay.workflow.clone(),
+            branch: selected_branch.clone(),
+            inputs: with_defaults(&inputs, fields.as_deref()),
+        };
+        let Some(guard) = guardrails::check(&self.config.guardrails, &target, Local::now()) else {
+            return self.run_replay();
+        };
 
-        let output = std::process::Command::new("gh")
-            .args(&args)
-            .output()?;
+        let args = dispatch_args(&repo_name, &replay.workflow);
+        let body = dispatch_body(&selected_branch, &inputs, fields.as_deref().unwrap_or_default());
+        self.ui.show_replays_popup = false;
+        self.ui.confirm_replay = true;
+        self.ui.dispatch_command_preview = command_preview(&args, Some(&body));
+        self.ui.dispatch_changes = input_changes(&inputs, fields.as_deref());
+        self.ui.dispatch_guard = Some(guard);
+        self.ui.dispatch_target = Some(target);
+        self.ui.confirm_input.clear();
+        self.ui.show_confirm_dispatch = true;
+        Ok(())
+    }
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("Replay dispatch failed: {}", stderr.trim()).into());
-        }
+    /// The inputs a replay's workflow declares on a branch, or None when
+    /// they can't be fetched.
+    fn replay_fields(&self, repo_name: &str, workflow: &str, branch: &str) -> Option<Vec<InputField>> {
+        let (_, mut fields) = self.github.fetch_workflow_inputs(repo_name, workflow, Some(branch)).ok()?;
+        input_rules::apply(&mut fields, &self.config.input_rules, repo_name, workflow);
+        Some(fields)
+    }
+
+    /// Run the selected replay with the currently selected branch.
+    pub fn run_replay(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let replay_idx = self.ui.replays_state.selected()
+            .ok_or("No replay selected.")?;
+        let replay = self.data.replays_list[replay_idx].clone();
+
+        let selected_repo_idx = self.selected_repo_real_index()
+            .ok_or("No repo selected.")?;
+        let repo_name = self.data.repos[selected_repo_idx].name.clone();
+
+        let selected_branch = match self.selected_branch_real_index() {
+            Some(idx) => self.data.branches[idx].clone(),
+            None => return Err("No branch selected.".into()),
+        };
+
+        let fields = self.replay_fields(&repo_name, &replay.workflow, &selected_branch).unwrap_or_default();
+        let inputs = replay_inputs(&replay, Some(&fields));
+        let (_, preview) = self.github.dispatch_with_inputs(&repo_name, &selected_branch, &replay.workflow, &inputs, &fields)?;
+        self.on_dispatched(TrackedRun {
+            repo: repo_name.clone(),
+            workflow: replay.workflow.clone(),
+            branch: selected_branch,
+            inputs,
+            dispatched_at: unix_now(),
+        });
+
+        let headline = if self.github.is_dry_run() {
+            "DRY RUN — replay not dispatched. Would have run:"
+        } else {
+            "✓ Replay dispatched!\n\nCommand:"
+        };
+        let prompt = if self.github.is_dry_run() {
+            self.dry_run_log()
+        } else {
+            self.log_prompt_hint("watch run logs", "continue")
+        };
 
         self.ui.show_replays_popup = false;
         self.ui.output_is_success = true;
         self.ui.output_is_error = false;
+        self.ui.dispatch_output_lines.clear();
         self.ui.output = Some(format!(
-            "✓ Replay dispatched!\n\nCommand:\n  {}\n\nInputs:\n{}\n\nPress 'l' to watch run logs, 'v' to open in browser, or any other key to continue.",
+            "{}\n  {}\n\nInputs:\n{}\n\n{}",
+            headline,
             preview,
             replay.inputs
                 .iter()
                 .map(|i| format!("  {} = {}", i.name, i.value))
                 .collect::<Vec<_>>()
-                .join("\n")
+                .join("\n"),
+            prompt,
         ));
-        self.ui.awaiting_log_prompt = true;
+        self.ui.awaiting_log_prompt = !self.github.is_dry_run();
         Ok(())
     }
 
@@ -699,29 +2141,38 @@ impl AppState {
             .ok_or("No repo selected.")?;
         let repo_name = &self.data.repos[selected_repo_idx].name;
 
-        let mut config = load_config();
-        if let Some(rc) = config.repos.iter_mut().find(|rc| rc.name == *repo_name) {
-            if replay_idx < rc.replays.len() {
-                let removed = rc.replays.remove(replay_idx);
-                let remaining = rc.replays.clone();
-                save_config(&config)?;
-                self.data.replays_list = remaining;
-
-                if self.data.replays_list.is_empty() {
-                    self.ui.show_replays_popup = false;
-                    self.ui.output = Some(format!("Deleted replay '{}'. No replays remaining.", removed.description));
+        if self.data.replays_list[replay_idx].source == ConfigSource::Team {
+            let file = self.config_paths.team.as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|| crate::config::TEAM_CONFIG_FILE.to_string());
+            return Err(format!("shared replays are read-only; edit {} instead", file).into());
+        }
+        // The popup lists user replays first, then the team's
+        let user_idx = self.data.replays_list[..replay_idx].iter()
+            .filter(|r| r.source == ConfigSource::User)
+            .count();
+
+        let mut config = self.reload_config()?;
+        if let Some(rc) = config.repos.iter_mut().find(|rc| rc.name == *repo_name)
+            && user_idx < rc.replays.len() {
+            let removed = rc.replays.remove(user_idx);
+            self.persist_config(&config)?;
+            self.data.replays_list.remove(replay_idx);
+
+            if self.data.replays_list.is_empty() {
+                self.ui.show_replays_popup = false;
+                self.ui.output = Some(format!("Deleted replay '{}'. No replays remaining.", removed.description));
+            } else {
+                // Adjust selection
+                let new_sel = if replay_idx >= self.data.replays_list.len() {
+                    self.data.replays_list.len() - 1
                 } else {
-                    // Adjust selection
-                    let new_sel = if replay_idx >= self.data.replays_list.len() {
-                        self.data.replays_list.len() - 1
-                    } else {
-                        replay_idx
-                    };
-                    self.ui.replays_state.select(Some(new_sel));
-                    self.ui.output = Some(format!("Deleted replay '{}'.", removed.description));
-                }
-                self.ui.output_is_error = false;
+                    replay_idx
+                };
+                self.ui.replays_state.select(Some(new_sel));
+                self.ui.output = Some(format!("Deleted replay '{}'.", removed.description));
             }
+            self.ui.output_is_error = false;
         }
         Ok(())
     }
@@ -752,6 +2203,158 @@ impl AppState {
         Ok(())
     }
 
+    /// Show the confirmation popup for the current selection, applying any
+    /// guardrail that protects it.
+    pub fn show_dispatch_confirmation(&mut self, preview: String) {
+        let repo = self.selected_repo_real_index().map(|i| self.data.repos[i].name.clone());
+        let branch = self.selected_branch_real_index().map(|i| self.data.branches[i].clone());
+        let workflow = self.selected_workflow_real_index().map(|i| self.data.workflows[i].name.clone());
+
+        self.ui.dispatch_target = match (repo, branch, workflow) {
+            (Some(repo), Some(branch), Some(workflow)) => Some(DispatchTarget {
+                repo,
+                workflow,
+                branch,
+                // Unset inputs count with the default the workflow will use
+                inputs: self.data.input_fields
+                    .iter()
+                    .filter(|f| (f.is_set() || !f.value.is_empty()) && f.is_shown(&self.data.input_fields))
+                    .map(|f| (f.name.clone(), f.value.clone()))
+                    .collect(),
+            }),
+            _ => None,
+        };
+        self.ui.dispatch_guard = self.ui.dispatch_target
+            .as_ref()
+            .and_then(|target| guardrails::check(&self.config.guardrails, target, Local::now()));
+        self.ui.dispatch_command_preview = preview;
+        self.ui.dispatch_changes = input_changes(&dispatch_inputs(&self.data.input_fields), Some(&self.data.input_fields));
+        self.ui.confirm_replay = false;
+        self.ui.confirm_input.clear();
+        self.ui.show_confirm_dispatch = true;
+    }
+
+    /// Close the confirmation popup without dispatching.
+    pub fn cancel_dispatch(&mut self) {
+        let blocked = self.ui.dispatch_guard.take().and_then(|guard| guard.blocked);
+        self.ui.dispatch_target = None;
+        self.ui.show_confirm_dispatch = false;
+        self.ui.confirm_replay = false;
+        self.ui.output_is_error = blocked.is_some();
+        self.ui.output = Some(match blocked {
+            Some(reason) => format!("Dispatch blocked: {}.", reason),
+            None => "Dispatch cancelled.".to_string(),
+        });
+    }
+
+    /// Dispatch whatever the confirmation popup was opened for.
+    pub fn confirm_dispatch(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        // The popup may have stayed open past the end of an allowed_hours
+        // window, or a reloaded config may protect the dispatch now
+        let guard = self.ui.dispatch_target
+            .as_ref()
+            .and_then(|target| guardrails::check(&self.config.guardrails, target, Local::now()));
+        if let Some(guard) = guard {
+            if let Some(reason) = guard.blocked.clone() {
+                self.cancel_dispatch();
+                return Err(format!("dispatch blocked: {}", reason).into());
+            }
+            // A new or edited rule asks again, with its own phrase
+            if self.ui.dispatch_guard.as_ref() != Some(&guard) {
+                self.ui.dispatch_guard = Some(guard);
+                self.ui.confirm_input.clear();
+                return Ok(());
+            }
+        }
+
+        self.ui.show_confirm_dispatch = false;
+        self.ui.dispatch_guard = None;
+        self.ui.dispatch_target = None;
+        self.ui.confirm_input.clear();
+        if std::mem::take(&mut self.ui.confirm_replay) {
+            self.run_replay()
+        } else {
+            self.run_workflow()
+        }
+    }
+
+    /// Unset every input, so the workflow defaults apply again.
+    pub fn reset_inputs(&mut self) {
+        for field in &mut self.data.input_fields {
+            field.unset();
+        }
+        self.ui.input_fields_editing = false;
+        self.keep_input_selection_shown();
+    }
+
+    /// Switch the inputs popup to the next order, keeping the same input
+    /// selected.
+    pub fn cycle_input_sort(&mut self) {
+        self.ui.input_sort = self.ui.input_sort.next();
+        let selected = self.data.input_fields.get(self.ui.input_fields_selected).map(|f| f.position);
+        input_rules::sort(&mut self.data.input_fields, self.ui.input_sort);
+        if let Some(idx) = selected.and_then(|p| self.data.input_fields.iter().position(|f| f.position == p)) {
+            self.ui.input_fields_selected = idx;
+        }
+    }
+
+    /// Move the inputs popup selection to the next or previous input that
+    /// isn't hidden by its condition, wrapping around at the ends if `wrap`.
+    pub fn move_input_selection(&mut self, forward: bool, wrap: bool) {
+        let fields = &self.data.input_fields;
+        let count = fields.len();
+        let mut idx = self.ui.input_fields_selected;
+        for _ in 0..count {
+            idx = match (forward, wrap) {
+                (true, _) if idx + 1 < count => idx + 1,
+                (false, _) if idx > 0 => idx - 1,
+                (true, true) => 0,
+                (false, true) => count - 1,
+                (_, false) => return,
+            };
+            if fields[idx].is_shown(fields) {
+                self.ui.input_fields_selected = idx;
+                return;
+            }
+        }
+    }
+
+    /// Move the selection off an input a changed value has hidden.
+    pub fn keep_input_selection_shown(&mut self) {
+        let fields = &self.data.input_fields;
+        if fields.get(self.ui.input_fields_selected).is_some_and(|f| !f.is_shown(fields)) {
+            self.move_input_selection(true, true);
+        }
+    }
+
+    /// What GitHub would reject about the current input values.
+    pub fn check_inputs(&self) -> Vec<InputProblem> {
+        validation::check(&dispatch_inputs(&self.data.input_fields), Some(&self.data.input_fields), &self.config.limits)
+    }
+
+    /// Check the input values before a dispatch. Problems are listed in the
+    /// output panel and the inputs popup opens on the first offending
+    /// field. Returns true when there were any.
+    pub fn report_input_problems(&mut self) -> bool {
+        self.ui.input_problems = self.check_inputs();
+        if self.ui.input_problems.is_empty() {
+            return false;
+        }
+        let list: Vec<String> = self.ui.input_problems.iter().map(|p| format!("- {}", p)).collect();
+        self.ui.output = Some(format!("GitHub would reject these inputs:\n\n{}", list.join("\n")));
+        self.ui.output_is_error = true;
+        self.ui.output_is_success = false;
+        let offending = self.ui.input_problems
+            .iter()
+            .find_map(|p| self.data.input_fields.iter().position(|f| Some(&f.name) == p.input.as_ref()));
+        if let Some(idx) = offending {
+            self.ui.input_fields_selected = idx;
+        }
+        self.ui.input_fields_editing = false;
+        self.ui.show_inputs_popup = true;
+        true
+    }
+
     /// Build the dispatch command preview string without executing it.
     /// Returns (args, preview_string) for display in confirmation popup.
     pub fn build_dispatch_command(&self) -> Result<(Vec<String>, String), Box<dyn std::error::Error>> {
@@ -767,24 +2370,164 @@ impl AppState {
             .ok_or("No workflow selected.")?;
         let workflow_filename = &self.data.workflows[selected_wf_idx].name;
 
-        let mut args = vec![
-            "workflow".to_string(),
-            "run".to_string(),
-            workflow_filename.to_string(),
-            "--repo".to_string(),
-            repo_name.to_string(),
-            "--ref".to_string(),
-            selected_branch.to_string(),
-        ];
-
-        for field in &self.data.input_fields {
-            if !field.value.is_empty() {
-                args.push("-f".to_string());
-                args.push(format!("{}={}", field.name, field.value));
-            }
+        if let Some(problem) = self.check_inputs().first() {
+            return Err(problem.to_string().into());
         }
 
-        let preview = format!("gh {}", args.join(" "));
+        let inputs = dispatch_inputs(&self.data.input_fields);
+        let args = dispatch_args(repo_name, workflow_filename);
+        let body = dispatch_body(selected_branch, &inputs, &self.data.input_fields);
+
+        let preview = command_preview(&args, Some(&body));
         Ok((args, preview))
     }
 }
+
+/// A replay's inputs, minus those the workflow's conditions hide.
+fn replay_inputs(replay: &ReplayConfig, fields: Option<&[InputField]>) -> Vec<(String, String)> {
+    let inputs: Vec<(String, String)> = replay.inputs.iter().map(|i| (i.name.clone(), i.value.clone())).collect();
+    let Some(fields) = fields else {
+        return inputs;
+    };
+    let applied = validation::with_values(fields, &inputs);
+    inputs
+        .into_iter()
+        .filter(|(name, _)| applied.iter().find(|f| f.name == *name).is_none_or(|f| f.is_shown(&applied)))
+        .collect()
+}
+
+/// The sent inputs plus the defaults the workflow uses for the shown
+/// inputs left out, which is what guardrails match against.
+fn with_defaults(inputs: &[(String, String)], fields: Option<&[InputField]>) -> Vec<(String, String)> {
+    let mut all = inputs.to_vec();
+    if let Some(fields) = fields {
+        let applied = validation::with_values(fields, inputs);
+        all.extend(
+            applied
+                .iter()
+                .filter(|f| !f.is_set() && !f.value.is_empty() && f.is_shown(&applied))
+                .map(|f| (f.name.clone(), f.value.clone())),
+        );
+    }
+    all
+}
+
+/// The sent inputs whose values differ from their workflow defaults.
+fn input_changes(inputs: &[(String, String)], fields: Option<&[InputField]>) -> Vec<InputChange> {
+    inputs
+        .iter()
+        .filter_map(|(name, value)| {
+            let default_value = fields.map(|fields| {
+                fields.iter().find(|f| f.name == *name).map(|f| f.default_value.clone()).unwrap_or_default()
+            });
+            (default_value.as_ref() != Some(value)).then(|| InputChange {
+                name: name.clone(),
+                default_value,
+                value: value.clone(),
+            })
+        })
+        .collect()
+}
+
+/// One-line "job › step: message" label for a run failure.
+pub fn failure_label(failure: &RunFailure) -> String {
+    let message = failure.message.lines().next().unwrap_or("");
+    match &failure.step {
+        Some(step) => format!("{} › {}: {}", failure.job, step, message),
+        None => format!("{}: {}", failure.job, message),
+    }
+}
+
+fn hook_context(run: &TrackedRun, event: HookEvent, run_url: &str, conclusion: &str) -> HookContext {
+    HookContext {
+        event,
+        repo: run.repo.clone(),
+        git_ref: run.branch.clone(),
+        workflow: run.workflow.clone(),
+        inputs: run.inputs.clone(),
+        run_url: run_url.to_string(),
+        conclusion: conclusion.to_string(),
+    }
+}
+
+/// Actions offered by the command palette, in the order listed there.
+const PALETTE_ACTIONS: &[Action] = &[
+    Action::EditInputs,
+    Action::Replays,
+    Action::WatchLogs,
+    Action::Failures,
+    Action::OpenInBrowser,
+    Action::AddRepo,
+    Action::ImportRepos,
+    Action::RemoveRepo,
+    Action::MoveRepoUp,
+    Action::MoveRepoDown,
+    Action::ToggleFavorite,
+    Action::SetAlias,
+    Action::SetGroup,
+    Action::Help,
+    Action::Quit,
+];
+
+/// Load the user config merged over the team config. A team file that fails
+/// to load is skipped with a warning, since it isn't the user's to fix; only
+/// a broken user file is an error. Team hooks and commands left out because
+/// they aren't trusted are a warning too.
+fn load_effective_config(paths: &ConfigPaths) -> Result<(Config, Option<String>), ConfigError> {
+    let user = load_config_from(&paths.user)?;
+    let Some(team_path) = &paths.team else {
+        return Ok((user, None));
+    };
+    match read_config_from(team_path) {
+        Ok(team) => {
+            let skipped = untrusted_team_commands(&user, &team);
+            let warning = (skipped > 0).then(|| format!(
+                "{} hook(s) and command source(s) in {} were not loaded; set `trust_team_commands: true` in your config to run them",
+                skipped,
+                team_path.display()
+            ));
+            Ok((merge_layers(user, team), warning))
+        }
+        Err(e) => Ok((user, Some(format!("Team config ignored: {}", e)))),
+    }
+}
+
+/// The configured keymap, or the defaults plus a warning listing what's wrong
+/// with the `keybindings` section.
+fn keymap_from_config(config: &Config) -> (Keymap, Option<String>) {
+    match Keymap::from_config(&config.keybindings) {
+        Ok(keymap) => (keymap, None),
+        Err(problems) => {
+            let list: Vec<String> = problems.iter().map(|p| format!("  - {}", p)).collect();
+            (Keymap::default(), Some(format!("Keybindings ignored, using the defaults:\n{}", list.join("\n"))))
+        }
+    }
+}
+
+/// The configured theme, or the built-in one without overrides plus a
+/// warning listing the colors that couldn't be parsed.
+fn theme_from_config(config: &Config) -> (Theme, Option<String>) {
+    let no_color = theme::no_color_requested();
+    match Theme::from_config(config.theme.name, &config.theme.colors, no_color) {
+        Ok(theme) => (theme, None),
+        Err(problems) => {
+            let list: Vec<String> = problems.iter().map(|p| format!("  - {}", p)).collect();
+            let fallback = Theme::from_config(config.theme.name, &Default::default(), no_color).unwrap_or_default();
+            (fallback, Some(format!("Theme colors ignored:\n{}", list.join("\n"))))
+        }
+    }
+}
+
+fn repos_from_config(config: &Config) -> Vec<Repo> {
+    config.repos.iter().map(repo_from_config).collect()
+}
+
+fn repo_from_config(rc: &RepoConfig) -> Repo {
+    Repo {
+        name: rc.name.clone(),
+        alias: rc.alias.clone(),
+        group: rc.group.clone(),
+        favorite: rc.favorite,
+        ..Default::default()
+    }
+}
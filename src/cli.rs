@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+
+/// Command-line options. Parsed by hand; there are only a few flags.
+#[derive(Debug, Default)]
+pub struct CliArgs {
+    pub dry_run: bool,                // record mutating GitHub calls instead of sending them
+    pub config: Option<PathBuf>,      // user config file instead of the default location
+    pub team_config: Option<PathBuf>, // team config instead of the nearest .dispatchrr.yml
+}
+
+impl CliArgs {
+    /// Dry-run setting from the command line or `DISPATCHRR_DRY_RUN`, which
+    /// take precedence over the config files. `None` leaves it to the config.
+    pub fn dry_run_override(&self) -> Option<bool> {
+        if self.dry_run {
+            return Some(true);
+        }
+        match std::env::var("DISPATCHRR_DRY_RUN").ok()?.to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Some(true),
+            "0" | "false" | "no" | "off" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+pub const USAGE: &str = "\
+Usage: lazy-dispatchr [OPTIONS]
+       lazy-dispatchr config validate [PATH]
+       lazy-dispatchr config migrate [PATH]
+
+Options:
+  --dry-run             Never dispatch; show what would have been sent instead
+                        [env: DISPATCHRR_DRY_RUN]
+  --config <PATH>       User config file [env: DISPATCHRR_CONFIG]
+  --team-config <PATH>  Team config file; defaults to the nearest .dispatchrr.yml
+                        [env: DISPATCHRR_TEAM_CONFIG]
+  -h, --help            Print this help
+
+Config commands (PATH defaults to the user config file):
+  validate    Check the config for errors without changing it
+  migrate     Upgrade the config to the current schema version, keeping a backup";
+
+/// What `main` should do after parsing the command line.
+#[derive(Debug)]
+pub enum CliCommand {
+    Run(CliArgs),
+    Help,
+    ConfigValidate(Option<PathBuf>),
+    ConfigMigrate(Option<PathBuf>),
+}
+
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<CliCommand, String> {
+    let mut args = args.into_iter().peekable();
+    if args.peek().map(String::as_str) == Some("config") {
+        args.next();
+        return parse_config_command(args);
+    }
+
+    let mut cli = CliArgs::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dry-run" => cli.dry_run = true,
+            "--config" => cli.config = Some(path_value(&arg, args.next())?),
+            "--team-config" => cli.team_config = Some(path_value(&arg, args.next())?),
+            "-h" | "--help" => return Ok(CliCommand::Help),
+            other => return Err(format!("unknown argument '{}'", other)),
+        }
+    }
+    Ok(CliCommand::Run(cli))
+}
+
+fn path_value(flag: &str, value: Option<String>) -> Result<PathBuf, String> {
+    value.map(PathBuf::from).ok_or_else(|| format!("{} needs a path", flag))
+}
+
+fn parse_config_command<I: Iterator<Item = String>>(mut args: I) -> Result<CliCommand, String> {
+    let sub = args.next().ok_or("missing config subcommand (validate or migrate)")?;
+    let path = args.next().map(PathBuf::from);
+    if let Some(extra) = args.next() {
+        return Err(format!("unexpected argument '{}'", extra));
+    }
+    match sub.as_str() {
+        "validate" => Ok(CliCommand::ConfigValidate(path)),
+        "migrate" => Ok(CliCommand::ConfigMigrate(path)),
+        "-h" | "--help" => Ok(CliCommand::Help),
+        other => Err(format!("unknown config subcommand '{}'", other)),
+    }
+}
@@ -1,33 +1,377 @@
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::domain::InputCondition;
+use crate::keymap::{Action, Keymap};
+use crate::theme::{Theme, ThemeName, ThemeSlot};
+
+/// Schema version written by this build. Bump it together with a new entry
+/// in `MIGRATIONS` whenever the file format changes incompatibly.
+pub const CONFIG_VERSION: u32 = 2;
 
 /// Serializable config format for ~/.config/dispatchrr/config.yml
-#[derive(serde::Serialize, serde::Deserialize, Debug, Default)]
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
 pub struct Config {
+    #[serde(default = "current_config_version")]
+    pub version: u32,
     #[serde(default)]
     pub repos: Vec<RepoConfig>,
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    #[serde(default)]
+    pub hooks: Vec<HookConfig>,
+    #[serde(default)]
+    pub guardrails: Vec<GuardrailConfig>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool, // same as passing --dry-run
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub keybindings: BTreeMap<Action, KeyList>, // replaces the default keys of each listed action
+    #[serde(default, skip_serializing_if = "ThemeConfig::is_default")]
+    pub theme: ThemeConfig,
+    #[serde(default, skip_serializing_if = "SuggestionsConfig::is_default")]
+    pub suggestions: SuggestionsConfig,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub input_sources: Vec<InputSourceConfig>,
+    #[serde(default, skip_serializing_if = "LimitsConfig::is_default")]
+    pub limits: LimitsConfig,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub input_rules: Vec<InputRuleConfig>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub trust_team_commands: bool, // run hooks and command input sources from the team file
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            repos: vec![],
+            notifications: NotificationsConfig::default(),
+            hooks: vec![],
+            guardrails: vec![],
+            dry_run: false,
+            keybindings: BTreeMap::new(),
+            theme: ThemeConfig::default(),
+            suggestions: SuggestionsConfig::default(),
+            input_sources: vec![],
+            limits: LimitsConfig::default(),
+            input_rules: vec![],
+            trust_team_commands: false,
+        }
+    }
+}
+
+fn current_config_version() -> u32 {
+    CONFIG_VERSION
+}
+
+/// Keys for one action: a single key (`dispatch: ctrl+d`) or a list
+/// (`down: [j, down]`). An empty list unbinds the action.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// A built-in theme plus individual color overrides, e.g.
+/// `colors: { focus: "#5f87ff", muted: gray }`.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
+pub struct ThemeConfig {
+    #[serde(default)]
+    pub name: ThemeName,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub colors: BTreeMap<ThemeSlot, String>,
+}
+
+impl ThemeConfig {
+    fn is_default(&self) -> bool {
+        self.name == ThemeName::default() && self.colors.is_empty()
+    }
+}
+
+impl KeyList {
+    pub fn keys(&self) -> &[String] {
+        match self {
+            KeyList::One(key) => std::slice::from_ref(key),
+            KeyList::Many(keys) => keys,
+        }
+    }
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub struct RepoConfig {
     pub name: String, // "owner/repo"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>, // shown instead of the name in the Repos panel
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>, // collapsible section in the Repos panel
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub favorite: bool,        // pinned to the top of the Repos panel
     #[serde(default)]
     pub replays: Vec<ReplayConfig>,
+    #[serde(skip)]
+    pub source: ConfigSource,
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
 pub struct ReplayConfig {
     pub workflow: String,          // workflow filename e.g. "deploy.yml"
     pub description: String,       // auto-generated from inputs e.g. "env=prod, version=1.0"
     pub inputs: Vec<ReplayInput>,  // saved input key=value pairs
+    #[serde(skip)]
+    pub source: ConfigSource,
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+/// Which config layer an entry was read from. Only `User` entries are ever
+/// written back; the team file is shared and edited by hand.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ConfigSource {
+    #[default]
+    User, // ~/.config/dispatchrr/config.yml
+    Team, // .dispatchrr.yml committed in a repository
+}
+
+impl ConfigSource {
+    pub fn label(self) -> &'static str {
+        match self {
+            ConfigSource::User => "user",
+            ConfigSource::Team => "team",
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
 pub struct ReplayInput {
     pub name: String,
     pub value: String,
 }
 
-fn config_path() -> PathBuf {
+/// Where input value suggestions come from.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct SuggestionsConfig {
+    #[serde(default = "default_true")]
+    pub history: bool, // values dispatched before, kept in history.yml
+    #[serde(default = "default_true")]
+    pub dynamic: bool, // recent tags / commit SHAs for version, tag and sha inputs
+}
+
+impl Default for SuggestionsConfig {
+    fn default() -> Self {
+        Self { history: true, dynamic: true }
+    }
+}
+
+impl SuggestionsConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// What GitHub accepts in one dispatch, checked before sending it.
+/// GitHub Enterprise Server releases that predate the 25-input limit
+/// need `max_inputs: 10`.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct LimitsConfig {
+    #[serde(default = "default_max_inputs")]
+    pub max_inputs: usize,  // inputs per dispatch
+    #[serde(default = "default_max_payload")]
+    pub max_payload: usize, // characters of the inputs JSON
+}
+
+fn default_max_inputs() -> usize {
+    25
+}
+
+fn default_max_payload() -> usize {
+    65_535
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self { max_inputs: default_max_inputs(), max_payload: default_max_payload() }
+    }
+}
+
+impl LimitsConfig {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// How to notify the user when a tracked run concludes.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct NotificationsConfig {
+    #[serde(default = "default_true")]
+    pub bell: bool,                    // ring the terminal bell
+    #[serde(default)]
+    pub desktop: DesktopNotification,  // OSC escape sequence understood by the terminal
+    #[serde(default)]
+    pub command: Vec<String>,          // e.g. ["notify-send", "Lazy-Dispatchrr"]; the message is appended
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            bell: true,
+            desktop: DesktopNotification::default(),
+            command: vec![],
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DesktopNotification {
+    #[default]
+    None,
+    Osc9,   // iTerm2, WezTerm, Windows Terminal, kitty
+    Osc777, // rxvt-unicode, foot, Ghostty, VTE-based terminals
+}
+
+/// A command or webhook fired when a dispatch is sent and/or its run concludes.
+///
+/// `{repo}`, `{ref}`, `{workflow}`, `{inputs}`, `{actor}`, `{run_url}`,
+/// `{conclusion}` and `{event}` are replaced in `command` arguments and `body`.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct HookConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,     // "owner/repo"; all repos when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workflow: Option<String>, // workflow filename; all workflows when omitted
+    #[serde(default = "all_hook_events")]
+    pub on: Vec<HookEvent>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub command: Vec<String>,     // program and arguments, run without a shell
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,      // JSON is POSTed here
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,     // JSON template for `url`; all fields are sent when omitted
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HookEvent {
+    Dispatch,
+    Conclusion,
+}
+
+fn all_hook_events() -> Vec<HookEvent> {
+    vec![HookEvent::Dispatch, HookEvent::Conclusion]
+}
+
+/// Marks matching dispatches as protected. Every criterion that is set must
+/// match; protected dispatches need a typed confirmation and may be limited
+/// to a time window.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
+pub struct GuardrailConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,      // "owner/repo"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workflow: Option<String>,  // workflow filename
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,    // glob, e.g. "release/*"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<ReplayInput>,  // required input values, e.g. env=prod
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirm: Option<String>,   // phrase to type; the repo name when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,   // shown in the warning banner
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocked_days: Vec<String>, // e.g. ["fri", "sat", "sun"]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_hours: Option<HourWindow>,
+}
+
+/// Where the values of a free-text input come from, e.g. the repo's
+/// releases for an `image_tag` input. The first matching source applies.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct InputSourceConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,     // "owner/repo"; all repos when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workflow: Option<String>, // workflow filename; all workflows when omitted
+    pub input: String,            // input name, glob, e.g. "*_tag"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<String>,      // a fixed list
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub command: Vec<String>,     // program and arguments, run without a shell; one value per output line
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github: Option<GitHubSource>,
+}
+
+/// Grouping and visibility of inputs, e.g. show `canary_percent` only when
+/// `strategy=canary`. Takes precedence over `[group: …]` and `[if: …]` tags
+/// in input descriptions.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct InputRuleConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,     // "owner/repo"; all repos when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workflow: Option<String>, // workflow filename; all workflows when omitted
+    pub input: String,            // input name, glob, e.g. "canary_*"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_if: Option<String>,  // e.g. "strategy=canary", see `InputCondition`
+}
+
+/// Repo data an input can be picked from.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GitHubSource {
+    Tags,
+    Releases,
+    Branches,
+    Environments,
+}
+
+/// Local-time hour window, `from` inclusive and `to` exclusive. A window
+/// with `from` after `to` runs past midnight, e.g. 22 to 6.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy)]
+pub struct HourWindow {
+    pub from: u32,
+    pub to: u32,
+}
+
+impl HourWindow {
+    pub fn contains(&self, hour: u32) -> bool {
+        if self.from <= self.to {
+            (self.from..self.to).contains(&hour)
+        } else {
+            hour >= self.from || hour < self.to
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Modification time and size of a file, used to notice edits made outside
+/// the app. A missing file has an empty stamp.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FileStamp {
+    modified: Option<std::time::SystemTime>,
+    len: Option<u64>,
+}
+
+impl FileStamp {
+    pub fn of(path: &Path) -> Self {
+        match std::fs::metadata(path) {
+            Ok(meta) => Self { modified: meta.modified().ok(), len: Some(meta.len()) },
+            Err(_) => Self::default(),
+        }
+    }
+}
+
+/// Name of the team-shared config file looked up from the working directory.
+pub const TEAM_CONFIG_FILE: &str = ".dispatchrr.yml";
+
+/// The user config file. `DISPATCHRR_CONFIG` overrides the default location.
+pub fn config_path() -> PathBuf {
+    if let Some(path) = std::env::var_os("DISPATCHRR_CONFIG").filter(|p| !p.is_empty()) {
+        return PathBuf::from(path);
+    }
     let base = if cfg!(windows) {
         // %LOCALAPPDATA% on Windows
         std::env::var("LOCALAPPDATA")
@@ -46,22 +390,438 @@ fn config_path() -> PathBuf {
     base.join("dispatchrr").join("config.yml")
 }
 
-pub fn load_config() -> Config {
-    let path = config_path();
+/// Why the config file could not be loaded or saved.
+#[derive(Debug)]
+pub enum ConfigError {
+    Io { path: PathBuf, source: std::io::Error },
+    Parse { path: PathBuf, line: Option<usize>, column: Option<usize>, message: String },
+    Serialize(serde_yaml::Error),
+    UnsupportedVersion { path: PathBuf, found: u32 }, // written by a newer build
+    Migration { path: PathBuf, from: u32, message: String },
+    Conflict { path: PathBuf }, // edited outside the app since it was last loaded
+    ReadOnly, // the app was started with a broken config and must not overwrite it
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            ConfigError::Parse { path, line: Some(line), column: Some(column), message } => {
+                write!(f, "{}:{}:{}: {}", path.display(), line, column, message)
+            }
+            ConfigError::Parse { path, message, .. } => write!(f, "{}: {}", path.display(), message),
+            ConfigError::Serialize(e) => write!(f, "could not serialize config: {}", e),
+            ConfigError::UnsupportedVersion { path, found } => write!(
+                f,
+                "{}: config version {} is newer than this build supports (version {}); please upgrade lazy-dispatchr",
+                path.display(), found, CONFIG_VERSION
+            ),
+            ConfigError::Migration { path, from, message } => {
+                write!(f, "{}: could not migrate config from version {}: {}", path.display(), from, message)
+            }
+            ConfigError::Conflict { path } => write!(
+                f,
+                "{} was changed outside the app; your change was not saved. The file has been reloaded, please try again",
+                path.display()
+            ),
+            ConfigError::ReadOnly => write!(f, "config is read-only because it failed to load; fix the file and restart"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io { source, .. } => Some(source),
+            ConfigError::Serialize(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Load the user config file. A missing file is an empty config; an
+/// unreadable or invalid one is an error, so callers never mistake it for
+/// "no repos". Older schema versions are upgraded in memory only; the file
+/// is rewritten by `config migrate` or the next save.
+pub fn load_config_from(path: &Path) -> Result<Config, ConfigError> {
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+    read_config_from(path)
+}
+
+/// Load a config file without modifying it, upgrading older schema
+/// versions in memory.
+pub fn read_config_from(path: &Path) -> Result<Config, ConfigError> {
+    let mut value = read_config_value(path)?;
+    if !value.as_mapping().is_some_and(|m| m.is_empty()) {
+        migrate_value(path, &mut value)?;
+    }
+    parse_config_value(path, value)
+}
+
+/// How many hooks and command input sources in the team layer
+/// `merge_layers` skips because the user doesn't trust them.
+pub fn untrusted_team_commands(user: &Config, team: &Config) -> usize {
+    if user.trust_team_commands {
+        return 0;
+    }
+    team.hooks.len() + team.input_sources.iter().filter(|s| !s.command.is_empty()).count()
+}
+
+/// Nearest `.dispatchrr.yml` in `start` or one of its parents.
+pub fn find_team_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(TEAM_CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+/// Where each config layer is read from, after CLI and environment overrides.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigPaths {
+    pub user: PathBuf,
+    pub team: Option<PathBuf>,
+}
+
+impl ConfigPaths {
+    /// Current stamps of the user and team files.
+    pub fn stamps(&self) -> (FileStamp, FileStamp) {
+        let team = self.team.as_deref().map(FileStamp::of).unwrap_or_default();
+        (FileStamp::of(&self.user), team)
+    }
+
+    /// Resolve the layer paths. Precedence, highest first: the CLI flag, the
+    /// `DISPATCHRR_CONFIG` / `DISPATCHRR_TEAM_CONFIG` variables, then the
+    /// defaults (the user config dir, and `.dispatchrr.yml` found by walking up
+    /// from the working directory).
+    pub fn resolve(user: Option<&Path>, team: Option<&Path>) -> Self {
+        let user = user.map(Path::to_path_buf).unwrap_or_else(config_path);
+        let team = team
+            .map(Path::to_path_buf)
+            .or_else(|| std::env::var_os("DISPATCHRR_TEAM_CONFIG").filter(|p| !p.is_empty()).map(PathBuf::from))
+            .or_else(|| std::env::current_dir().ok().and_then(|dir| find_team_config(&dir)));
+        Self { user, team }
+    }
+}
+
+/// Merge the team layer under the user layer.
+///
+/// Repos and replays from both files are combined (user entries first), and
+/// every replay remembers which file it came from. Hooks and guardrails from
+/// both apply, so a personal file can add protection but never remove the
+/// team's; team hooks only when trusted, see below. Input sources and rules from both apply, user entries first so
+/// they win. Dispatch limits take the lower value of the two files.
+/// Notifications, keybindings, the theme and suggestions are personal and
+/// only read from the user file.
+///
+/// Team hooks and input sources that run a `command` are dropped unless the
+/// user file sets `trust_team_commands`: anyone who can commit to the
+/// repository could otherwise run programs on this machine, or have every
+/// dispatch's inputs posted to a URL of their choosing.
+pub fn merge_layers(user: Config, mut team: Config) -> Config {
+    if !user.trust_team_commands {
+        team.hooks.clear();
+        team.input_sources.retain(|source| source.command.is_empty());
+    }
+    let mut merged = user;
+    for mut team_repo in team.repos {
+        for replay in &mut team_repo.replays {
+            replay.source = ConfigSource::Team;
+        }
+        match merged.repos.iter_mut().find(|rc| rc.name == team_repo.name) {
+            Some(rc) => {
+                rc.alias = rc.alias.take().or(team_repo.alias);
+                rc.group = rc.group.take().or(team_repo.group);
+                rc.replays.extend(team_repo.replays);
+            }
+            None => {
+                team_repo.source = ConfigSource::Team;
+                merged.repos.push(team_repo);
+            }
+        }
+    }
+    merged.hooks.extend(team.hooks);
+    merged.guardrails.extend(team.guardrails);
+    merged.input_sources.extend(team.input_sources);
+    merged.input_rules.extend(team.input_rules);
+    merged.limits.max_inputs = merged.limits.max_inputs.min(team.limits.max_inputs);
+    merged.limits.max_payload = merged.limits.max_payload.min(team.limits.max_payload);
+    merged.dry_run |= team.dry_run;
+    merged
+}
+
+/// Save a config to `path`, keeping the previous file as `<path>.bak`.
+///
+/// The new contents are written to a temporary file first and renamed over
+/// the old one, so a crash mid-write never leaves a truncated config behind.
+pub fn save_config_to(path: &Path, config: &Config) -> Result<(), ConfigError> {
+    let yaml = serde_yaml::to_string(config).map_err(ConfigError::Serialize)?;
+    write_config_file(path, &yaml, "bak")
+}
+
+fn io_error(path: &Path) -> impl FnOnce(std::io::Error) -> ConfigError {
+    let path = path.to_path_buf();
+    move |source| ConfigError::Io { path, source }
+}
+
+/// `<path>.<suffix>`, e.g. `config.yml.bak`.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", suffix));
+    PathBuf::from(name)
+}
+
+fn write_config_file(path: &Path, contents: &str, backup_suffix: &str) -> Result<(), ConfigError> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(io_error(parent))?;
+    }
+
     if path.exists() {
-        let contents = std::fs::read_to_string(&path).unwrap_or_default();
-        serde_yaml::from_str(&contents).unwrap_or_default()
-    } else {
-        Config::default()
+        let backup = with_suffix(path, backup_suffix);
+        std::fs::copy(path, &backup).map_err(io_error(&backup))?;
+    }
+
+    let tmp = with_suffix(path, "tmp");
+    std::fs::write(&tmp, contents).map_err(io_error(&tmp))?;
+    std::fs::rename(&tmp, path).map_err(io_error(path))?;
+    Ok(())
+}
+
+fn read_config_value(path: &Path) -> Result<serde_yaml::Value, ConfigError> {
+    let contents = std::fs::read_to_string(path).map_err(io_error(path))?;
+    if contents.trim().is_empty() {
+        return Ok(serde_yaml::Value::Mapping(Default::default()));
+    }
+    serde_yaml::from_str(&contents).map_err(|e| parse_error(path, e))
+}
+
+fn parse_config_value(path: &Path, value: serde_yaml::Value) -> Result<Config, ConfigError> {
+    serde_yaml::from_value(value).map_err(|e| parse_error(path, e))
+}
+
+fn parse_error(path: &Path, e: serde_yaml::Error) -> ConfigError {
+    ConfigError::Parse {
+        path: path.to_path_buf(),
+        line: e.location().map(|l| l.line()),
+        column: e.location().map(|l| l.column()),
+        message: e.to_string(),
     }
 }
 
-pub fn save_config(config: &Config) -> Result<(), Box<dyn std::error::Error>> {
-    let path = config_path();
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
+// --- Schema versions & migrations ---
+
+/// One step of the migration pipeline: upgrades a raw config from
+/// `MIGRATIONS[i].0` to the next version.
+type Migration = (u32, fn(&mut serde_yaml::Mapping) -> Result<(), String>);
+
+const MIGRATIONS: &[Migration] = &[(1, migrate_v1_to_v2)];
+
+/// Files from before versioning (no `version` key) are version 1.
+fn config_version(path: &Path, value: &serde_yaml::Value) -> Result<u32, ConfigError> {
+    match &value["version"] {
+        serde_yaml::Value::Null => Ok(1),
+        version => version.as_u64().and_then(|v| u32::try_from(v).ok()).ok_or_else(|| ConfigError::Parse {
+            path: path.to_path_buf(),
+            line: None,
+            column: None,
+            message: format!(
+                "`version` must be a whole number, found {}",
+                serde_yaml::to_string(version).unwrap_or_default().trim()
+            ),
+        }),
     }
-    let yaml = serde_yaml::to_string(config)?;
-    std::fs::write(&path, yaml)?;
+}
+
+/// v1 → v2: introduces the `version` key. The v1 layout (`repos` with
+/// `replays`) is otherwise unchanged.
+fn migrate_v1_to_v2(_config: &mut serde_yaml::Mapping) -> Result<(), String> {
     Ok(())
 }
+
+/// Run every migration needed to bring `value` to `CONFIG_VERSION`.
+/// Returns the version the value started at.
+fn migrate_value(path: &Path, value: &mut serde_yaml::Value) -> Result<u32, ConfigError> {
+    let found = config_version(path, value)?;
+    if found > CONFIG_VERSION {
+        return Err(ConfigError::UnsupportedVersion { path: path.to_path_buf(), found });
+    }
+
+    let mapping = value.as_mapping_mut().ok_or_else(|| ConfigError::Migration {
+        path: path.to_path_buf(),
+        from: found,
+        message: "top level must be a mapping".to_string(),
+    })?;
+
+    let mut version = found;
+    for (from, migrate) in MIGRATIONS {
+        if *from == version {
+            migrate(mapping).map_err(|message| ConfigError::Migration {
+                path: path.to_path_buf(),
+                from: version,
+                message,
+            })?;
+            version = from + 1;
+        }
+    }
+
+    // Stamp the new version as the first key, where `save_config` puts it too
+    mapping.remove("version");
+    let mut stamped = serde_yaml::Mapping::new();
+    stamped.insert("version".into(), version.into());
+    stamped.extend(std::mem::take(mapping));
+    *mapping = stamped;
+    Ok(found)
+}
+
+/// Upgrade the file at `path` to `CONFIG_VERSION` in place, keeping the old
+/// file as `<path>.v<N>.bak`. Returns the previous version when a migration
+/// ran, `None` when the file was already current.
+pub fn migrate_config_file(path: &Path) -> Result<Option<u32>, ConfigError> {
+    let mut value = read_config_value(path)?;
+    if value.as_mapping().is_some_and(|m| m.is_empty()) {
+        return Ok(None);
+    }
+    let found = migrate_value(path, &mut value)?;
+    if found == CONFIG_VERSION {
+        return Ok(None);
+    }
+
+    // Make sure the result is a valid config before touching the file
+    parse_config_value(path, value.clone())?;
+    let yaml = serde_yaml::to_string(&value).map_err(ConfigError::Serialize)?;
+    write_config_file(path, &yaml, &format!("v{}.bak", found))?;
+    Ok(Some(found))
+}
+
+/// Load `path` without modifying it and report problems a load wouldn't
+/// catch. Returns the file's schema version and the list of problems.
+pub fn validate_config_file(path: &Path) -> Result<(u32, Vec<String>), ConfigError> {
+    let mut value = read_config_value(path)?;
+    let found = migrate_value(path, &mut value)?;
+    let config = parse_config_value(path, value)?;
+
+    let mut problems = Vec::new();
+    if found < CONFIG_VERSION {
+        problems.push(format!(
+            "schema version {} is outdated (current: {}); run `lazy-dispatchr config migrate`",
+            found, CONFIG_VERSION
+        ));
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for repo in &config.repos {
+        if repo.name.split_once('/').is_none_or(|(owner, name)| owner.is_empty() || name.is_empty()) {
+            problems.push(format!("repo '{}': name must be 'owner/repo'", repo.name));
+        }
+        if !seen.insert(repo.name.as_str()) {
+            problems.push(format!("repo '{}' is listed more than once", repo.name));
+        }
+        for replay in &repo.replays {
+            if replay.workflow.is_empty() {
+                problems.push(format!("repo '{}': a replay has no workflow", repo.name));
+            }
+        }
+    }
+    for (i, hook) in config.hooks.iter().enumerate() {
+        if hook.command.is_empty() && hook.url.is_none() {
+            problems.push(format!("hooks[{}]: needs a `command` or a `url`", i));
+        }
+    }
+    for (i, rule) in config.guardrails.iter().enumerate() {
+        for day in &rule.blocked_days {
+            if crate::guardrails::parse_weekday(day).is_none() {
+                problems.push(format!("guardrails[{}]: unknown blocked day '{}'", i, day));
+            }
+        }
+        if let Some(window) = rule.allowed_hours
+            && (window.from == window.to || window.from > 23 || window.to > 24)
+        {
+            problems.push(format!(
+                "guardrails[{}]: allowed_hours {}-{} is not a valid window",
+                i, window.from, window.to
+            ));
+        }
+    }
+
+    if config.limits.max_inputs == 0 {
+        problems.push("limits.max_inputs: must be at least 1".to_string());
+    }
+
+    for (i, source) in config.input_sources.iter().enumerate() {
+        if source.input.is_empty() {
+            problems.push(format!("input_sources[{}]: needs an `input` name", i));
+        }
+        let kinds = [!source.values.is_empty(), !source.command.is_empty(), source.github.is_some()];
+        match kinds.iter().filter(|&&set| set).count() {
+            0 => problems.push(format!("input_sources[{}]: needs `values`, a `command` or `github`", i)),
+            1 => {}
+            _ => problems.push(format!("input_sources[{}]: set only one of `values`, `command` and `github`", i)),
+        }
+    }
+
+    for (i, rule) in config.input_rules.iter().enumerate() {
+        if rule.input.is_empty() {
+            problems.push(format!("input_rules[{}]: needs an `input` name", i));
+        }
+        if rule.group.is_none() && rule.show_if.is_none() {
+            problems.push(format!("input_rules[{}]: needs a `group` or `show_if`", i));
+        }
+        if let Some(Err(e)) = rule.show_if.as_deref().map(InputCondition::parse) {
+            problems.push(format!("input_rules[{}]: show_if {}", i, e));
+        }
+    }
+
+    if let Err(keymap_problems) = Keymap::from_config(&config.keybindings) {
+        problems.extend(keymap_problems.into_iter().map(|p| format!("keybindings: {}", p)));
+    }
+    if let Err(theme_problems) = Theme::from_config(config.theme.name, &config.theme.colors, false) {
+        problems.extend(theme_problems.into_iter().map(|p| format!("theme: {}", p)));
+    }
+
+    Ok((found, problems))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(yaml: &str) -> Config {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    const TEAM: &str = r#"
+hooks:
+  - command: [notify-send, dispatched]
+  - url: https://example.com/collect
+input_sources:
+  - input: tag
+    command: [git, tag]
+  - input: env
+    values: [staging, prod]
+"#;
+
+    #[test]
+    fn team_hooks_and_commands_need_trust() {
+        let user = parse("hooks:\n  - url: https://example.com/mine\n");
+        let team = parse(TEAM);
+        assert_eq!(untrusted_team_commands(&user, &team), 3);
+
+        let merged = merge_layers(user, team);
+        assert_eq!(merged.hooks.len(), 1);
+        assert_eq!(merged.hooks[0].url.as_deref(), Some("https://example.com/mine"));
+        assert_eq!(merged.input_sources.len(), 1);
+        assert_eq!(merged.input_sources[0].input, "env");
+    }
+
+    #[test]
+    fn trusted_team_hooks_and_commands_are_kept() {
+        let user = parse("trust_team_commands: true\n");
+        let team = parse(TEAM);
+        assert_eq!(untrusted_team_commands(&user, &team), 0);
+
+        let merged = merge_layers(user, team);
+        assert_eq!(merged.hooks.len(), 2);
+        assert_eq!(merged.input_sources.len(), 2);
+    }
+}
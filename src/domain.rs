@@ -4,6 +4,20 @@ pub struct Repo {
     pub name: String,
     pub branches: Vec<String>,
     pub workflows: Vec<String>,
+    pub alias: Option<String>,
+    pub group: Option<String>,
+    pub favorite: bool,
+}
+
+impl Repo {
+    /// Text shown in the Repos panel: the alias with the full name after it,
+    /// or just the name.
+    pub fn label(&self) -> String {
+        match &self.alias {
+            Some(alias) => format!("{} ({})", alias, self.name),
+            None => self.name.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -22,5 +36,295 @@ pub struct InputField {
     pub required: bool,
     pub default_value: String,
     pub options: Vec<String>,  // for choice type
-    pub value: String,         // user-entered value
+    pub value: String,         // user-entered value; the default while unset
+    pub state: InputState,
+    pub source: Option<String>, // configured input source `options` came from, for free-text inputs
+    pub group: Option<String>,  // heading the input is listed under in the inputs popup
+    pub show_if: Option<InputCondition>, // hidden and not sent unless this holds
+    pub position: usize,        // index in the workflow's `inputs:`, to restore declaration order
+}
+
+/// When an input applies, going by another input's value:
+/// `strategy=canary|blue_green`, `strategy!=rolling`, `notify` or `!notify`.
+/// A bare name holds when the value is non-empty and not `false`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputCondition {
+    pub input: String,
+    pub values: Vec<String>, // empty for a bare name
+    pub negate: bool,
+}
+
+impl InputCondition {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let (input, values, negate) = if let Some((input, values)) = text.split_once("!=") {
+            (input, Some(values), true)
+        } else if let Some((input, values)) = text.split_once('=') {
+            (input, Some(values), false)
+        } else if let Some(input) = text.strip_prefix('!') {
+            (input, None, true)
+        } else {
+            (text, None, false)
+        };
+        let input = input.trim();
+        if input.is_empty() || input.contains(char::is_whitespace) {
+            return Err(format!("'{}' is not `input`, `!input`, `input=value` or `input!=value`", text));
+        }
+        let values = values
+            .map(|values| values.split('|').map(|v| v.trim().to_string()).collect())
+            .unwrap_or_default();
+        Ok(Self { input: input.to_string(), values, negate })
+    }
+
+    pub fn holds(&self, value: &str) -> bool {
+        let matched = if self.values.is_empty() {
+            !value.is_empty() && value != "false"
+        } else {
+            self.values.iter().any(|v| v == value)
+        };
+        matched != self.negate
+    }
+}
+
+impl std::fmt::Display for InputCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.values.is_empty(), self.negate) {
+            (true, false) => write!(f, "{}", self.input),
+            (true, true) => write!(f, "!{}", self.input),
+            (false, false) => write!(f, "{}={}", self.input, self.values.join("|")),
+            (false, true) => write!(f, "{}!={}", self.input, self.values.join("|")),
+        }
+    }
+}
+
+/// Whether an input goes out with the dispatch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputState {
+    Unset, // left out, so the workflow's default applies
+    Set,   // sent as is, even when empty
+}
+
+impl InputField {
+    /// Give the input a value that is sent with the dispatch, even when empty.
+    pub fn set_value(&mut self, value: impl Into<String>) {
+        self.value = value.into();
+        self.state = InputState::Set;
+    }
+
+    /// Leave the input out of the dispatch again, showing its default.
+    pub fn unset(&mut self) {
+        self.value = self.default_value.clone();
+        self.state = InputState::Unset;
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.state == InputState::Set
+    }
+
+    /// Whether the input applies given the values of the others: its
+    /// `show_if` holds and the input it looks at is shown itself. Conditions
+    /// on inputs the workflow doesn't declare are ignored; circular ones
+    /// hide every input involved.
+    pub fn is_shown(&self, fields: &[InputField]) -> bool {
+        self.is_shown_within(fields, fields.len())
+    }
+
+    fn is_shown_within(&self, fields: &[InputField], depth: usize) -> bool {
+        let Some(condition) = &self.show_if else {
+            return true;
+        };
+        let Some(other) = fields.iter().find(|f| f.name == condition.input) else {
+            return true;
+        };
+        depth > 0 && condition.holds(&other.value) && other.is_shown_within(fields, depth - 1)
+    }
+
+    /// Whether the value is something other than the workflow default.
+    pub fn differs_from_default(&self) -> bool {
+        self.value != self.default_value
+    }
+
+    /// Whether the value is picked from `options`: choices always are,
+    /// free-text inputs when a source filled them.
+    pub fn has_options(&self) -> bool {
+        !self.options.is_empty() && (self.input_type == "choice" || self.source.is_some())
+    }
+
+    /// Whether the value is meant to be JSON: the description has a `[json]`
+    /// tag, or `json` is a word of the name (`json`, `payload_json`,
+    /// `json-config`). The value itself isn't looked at, since strings like
+    /// `[skip ci] deploy` start like JSON too.
+    pub fn is_json(&self) -> bool {
+        self.description.to_lowercase().contains("[json]")
+            || self.name.to_lowercase().split(['_', '-']).any(|word| word == "json")
+    }
+
+    /// Why a JSON value doesn't parse, shown as a warning since GitHub
+    /// accepts any string. Empty values are left to `required`.
+    pub fn json_problem(&self) -> Option<String> {
+        if !self.is_json() || self.value.trim().is_empty() {
+            return None;
+        }
+        serde_json::from_str::<serde_json::Value>(&self.value).err().map(|e| format!("invalid JSON: {}", e))
+    }
+
+    /// Why GitHub would reject the value for this input's type. An unset
+    /// input is only a problem when it is required and has no default.
+    pub fn value_problem(&self) -> Option<String> {
+        if !self.is_set() {
+            return (self.required && self.default_value.is_empty()).then(|| "required, but unset".to_string());
+        }
+        let value = self.value.as_str();
+        match self.input_type.as_str() {
+            "boolean" if value != "true" && value != "false" => Some(format!("'{}' is not true or false", value)),
+            "choice" if !self.options.is_empty() && !self.options.iter().any(|o| o == value) => {
+                Some(format!("'{}' is not one of: {}", value, self.options.join(", ")))
+            }
+            // The same parse `dispatch_body` sends the value with, so NaN,
+            // inf or 1e400 are caught here rather than sent as strings
+            "number" if value.trim().parse::<serde_json::Number>().is_err() => Some(format!("'{}' is not a number", value)),
+            _ => None,
+        }
+    }
+}
+
+/// A single "why it failed" entry for a workflow run, gathered from check-run
+/// annotations or `##[error]` / `::error` markers in the run log.
+#[derive(Debug, Clone)]
+pub struct RunFailure {
+    pub job: String,
+    pub step: Option<String>,
+    pub message: String,
+    pub log_line: Option<usize>, // index into the fetched run log, for jump-to-context
+}
+
+/// A workflow run as listed by `gh run list`.
+#[derive(Debug, Clone)]
+pub struct RunSummary {
+    pub id: u64,
+    pub status: String,     // "queued", "in_progress", "completed", ...
+    pub conclusion: String, // "success", "failure", "cancelled", ... (empty until completed)
+    pub created_at: String, // RFC 3339, e.g. "2026-01-08T20:35:32Z"
+    pub url: String,
+}
+
+/// A repo offered by the bulk-import popup.
+#[derive(Debug, Clone)]
+pub struct RepoListing {
+    pub name: String,        // "owner/repo"
+    pub description: String,
+    pub dispatchable: usize, // workflow files with a `workflow_dispatch` trigger
+}
+
+/// Inputs for unit tests, shaped like the ones `fetch_workflow_inputs`
+/// reads from a workflow file.
+#[cfg(test)]
+pub mod fixtures {
+    use super::*;
+
+    /// An optional input left unset, so it holds its default.
+    pub fn input(name: &str, input_type: &str, default_value: &str) -> InputField {
+        InputField {
+            name: name.to_string(),
+            description: String::new(),
+            input_type: input_type.to_string(),
+            required: false,
+            default_value: default_value.to_string(),
+            options: Vec::new(),
+            value: default_value.to_string(),
+            state: InputState::Unset,
+            source: None,
+            group: None,
+            show_if: None,
+            position: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixtures::input;
+    use super::*;
+
+    fn field(name: &str, value: &str, show_if: Option<&str>) -> InputField {
+        let mut field = input(name, "string", "");
+        field.set_value(value);
+        field.show_if = show_if.map(|c| InputCondition::parse(c).unwrap());
+        field
+    }
+
+    #[test]
+    fn parses_conditions() {
+        let condition = InputCondition::parse(" strategy = canary | blue_green ").unwrap();
+        assert_eq!(condition.input, "strategy");
+        assert_eq!(condition.values, vec!["canary", "blue_green"]);
+        assert!(!condition.negate);
+        assert_eq!(condition.to_string(), "strategy=canary|blue_green");
+
+        for text in ["notify", "!notify", "strategy!=rolling"] {
+            assert_eq!(InputCondition::parse(text).unwrap().to_string(), text);
+        }
+        assert!(InputCondition::parse("").is_err());
+        assert!(InputCondition::parse("!").is_err());
+        assert!(InputCondition::parse("two words=x").is_err());
+    }
+
+    #[test]
+    fn conditions_hold() {
+        let bare = InputCondition::parse("notify").unwrap();
+        assert!(bare.holds("true"));
+        assert!(bare.holds("yes"));
+        assert!(!bare.holds("false"));
+        assert!(!bare.holds(""));
+        assert!(InputCondition::parse("!notify").unwrap().holds("false"));
+
+        let not_rolling = InputCondition::parse("strategy!=rolling|recreate").unwrap();
+        assert!(not_rolling.holds("canary"));
+        assert!(!not_rolling.holds("recreate"));
+    }
+
+    #[test]
+    fn inputs_are_hidden_with_the_inputs_they_depend_on() {
+        let fields = vec![
+            field("deploy", "false", None),
+            field("strategy", "canary", Some("deploy")),
+            field("weight", "10", Some("strategy=canary")),
+            field("other", "", Some("missing=x")),
+        ];
+        assert!(!fields[1].is_shown(&fields));
+        assert!(!fields[2].is_shown(&fields));
+        assert!(fields[3].is_shown(&fields));
+
+        let circular = vec![field("a", "x", Some("b")), field("b", "x", Some("a"))];
+        assert!(!circular[0].is_shown(&circular));
+    }
+
+    #[test]
+    fn unset_restores_the_default() {
+        let mut input = field("note", "", None);
+        input.default_value = "hi".to_string();
+        assert!(input.is_set());
+        input.unset();
+        assert!(!input.is_set());
+        assert_eq!(input.value, "hi");
+        input.set_value("");
+        assert!(input.is_set());
+        assert_eq!(input.value, "");
+    }
+
+    #[test]
+    fn json_inputs_go_by_tag_or_name() {
+        let mut payload = input("payload", "string", "");
+        payload.set_value("[skip ci] deploy");
+        assert!(!payload.is_json());
+        assert_eq!(payload.json_problem(), None);
+
+        payload.description = "Deploy payload [JSON]".to_string();
+        assert!(payload.is_json());
+        assert!(payload.json_problem().unwrap().starts_with("invalid JSON: "));
+
+        assert!(input("payload_json", "string", "").is_json());
+        assert!(input("json-config", "string", "").is_json());
+        assert!(!input("jsonish", "string", "").is_json());
+    }
 }
@@ -1,504 +1,971 @@
 use crate::{
-    app::{AppState, Focus},
+    app::{AppState, Focus, PaletteCommand, RepoField},
+    keymap::{Action, Scope},
     ui::render,
 };
 use color_eyre::eyre::Result;
-use crossterm::event::{self, Event, KeyCode};
+use crossterm::{
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event, KeyCode,
+        KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    },
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
 use ratatui::DefaultTerminal;
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+
+/// How long to wait for input before redrawing to pick up background events.
+const TICK_RATE: Duration = Duration::from_millis(250);
 
 pub fn run(mut terminal: DefaultTerminal, state: &mut AppState) -> Result<()> {
     loop {
+        state.reload_changed_config();
+        state.process_background_events();
         terminal.draw(|frame| render(frame, state))?;
 
-        if let Event::Key(key) = event::read()? {
-            if key.kind == event::KeyEventKind::Press {
-                // Handle help popup — any key dismisses it
-                if state.ui.show_help_popup {
-                    state.ui.show_help_popup = false;
-                    continue;
+        if !event::poll(TICK_RATE)? {
+            continue;
+        }
+
+        let event = event::read()?;
+        if let Event::Paste(text) = &event {
+            state.paste(text);
+            continue;
+        }
+        if let Event::Mouse(mouse) = event {
+            if handle_mouse(state, mouse) {
+                break;
+            }
+            continue;
+        }
+
+        if let Event::Key(key) = event
+            && key.kind == event::KeyEventKind::Press {
+            // Handle the config error screen before anything else
+            if state.ui.config_error.is_some() {
+                match key.code {
+                    KeyCode::Char('o') => {
+                        let path = state.config_paths.user.clone();
+                        match open_in_editor(&mut terminal, &path) {
+                            Ok(()) => state.retry_config_load(),
+                            Err(e) => state.ui.config_error = Some(format!("Could not open editor: {}", e)),
+                        }
+                    }
+                    KeyCode::Char('c') => {
+                        state.ui.config_error = None;
+                        state.ui.output = Some("Continuing with a read-only config: changes will not be saved until the file is fixed and the app restarted.".to_string());
+                        state.ui.output_is_error = true;
+                    }
+                    KeyCode::Char('q') | KeyCode::Esc => break,
+                    _ => {}
                 }
+                continue;
+            }
 
-                // Handle add-repo popup input first
-                if state.ui.show_add_repo_popup {
-                    match key.code {
-                        KeyCode::Esc => {
-                            state.ui.show_add_repo_popup = false;
-                            state.ui.add_repo_owner.clear();
-                            state.ui.add_repo_name.clear();
-                            state.ui.add_repo_focus_owner = true;
+            // Handle help popup — any key dismisses it
+            if state.ui.show_help_popup {
+                state.ui.show_help_popup = false;
+                continue;
+            }
+
+            // Handle add-repo popup input first
+            if state.ui.show_add_repo_popup {
+                match key.code {
+                    KeyCode::Esc => state.close_add_repo(),
+                    // Tab completes the highlighted suggestion, or moves on
+                    KeyCode::Tab if state.accept_add_repo_suggestion() => {}
+                    KeyCode::Tab | KeyCode::BackTab => state.switch_add_repo_field(),
+                    KeyCode::Down => {
+                        let count = state.add_repo_suggestions().len();
+                        if count > 0 {
+                            state.ui.add_repo_suggestion = (state.ui.add_repo_suggestion + 1) % count;
+                        }
+                    }
+                    KeyCode::Up => {
+                        let count = state.add_repo_suggestions().len();
+                        if count > 0 {
+                            state.ui.add_repo_suggestion = (state.ui.add_repo_suggestion + count - 1) % count;
                         }
-                        KeyCode::Tab | KeyCode::BackTab => {
-                            state.ui.add_repo_focus_owner = !state.ui.add_repo_focus_owner;
+                    }
+                    KeyCode::Enter => {
+                        if let Err(e) = state.submit_add_repo() {
+                            state.ui.output = Some(format!("Error adding repo: {}", e));
+                            state.ui.output_is_error = true;
+                        } else {
+                            state.ui.output_is_error = false;
                         }
+                    }
+                    KeyCode::Backspace if !state.ui.add_repo_focus_owner && state.ui.add_repo_name.is_empty() => {
+                        // Backspace past the start of the repo field goes back to the owner
+                        state.ui.add_repo_focus_owner = true;
+                        state.ui.add_repo_suggestion = 0;
+                    }
+                    KeyCode::Char(c) if !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+                        state.type_into_add_repo(c)
+                    }
+                    _ => state.edit_add_repo_field(&key),
+                }
+                continue;
+            }
+
+            // Handle the bulk-import popup
+            if let Some(import) = state.ui.repo_import.as_mut() {
+                let ctrl = key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL);
+                if import.listings.is_none() {
+                    // Owner prompt
+                    match key.code {
+                        KeyCode::Esc => state.ui.repo_import = None,
                         KeyCode::Enter => {
-                            let owner = state.ui.add_repo_owner.clone();
-                            let name = state.ui.add_repo_name.clone();
-                            if owner.is_empty() || name.is_empty() {
-                                state.ui.output = Some("Both owner and repo fields are required.".to_string());
+                            if let Err(e) = state.fetch_import_listings() {
+                                state.ui.repo_import = None;
+                                state.ui.output = Some(format!("Error listing repos: {}", e));
                                 state.ui.output_is_error = true;
-                            } else {
-                                state.ui.show_add_repo_popup = false;
-                                if let Err(e) = state.add_repo(&owner, &name) {
-                                    state.ui.output = Some(format!("Error adding repo: {}", e));
-                                    state.ui.output_is_error = true;
-                                } else {
-                                    state.ui.output_is_error = false;
-                                }
-                                state.ui.add_repo_owner.clear();
-                                state.ui.add_repo_name.clear();
-                                state.ui.add_repo_focus_owner = true;
                             }
                         }
                         KeyCode::Backspace => {
-                            if state.ui.add_repo_focus_owner {
-                                state.ui.add_repo_owner.pop();
-                            } else {
-                                state.ui.add_repo_name.pop();
-                            }
-                        }
-                        KeyCode::Char(c) => {
-                            if state.ui.add_repo_focus_owner {
-                                state.ui.add_repo_owner.push(c);
-                            } else {
-                                state.ui.add_repo_name.push(c);
-                            }
+                            import.owner.pop();
                         }
+                        KeyCode::Char(c) => import.owner.push(c),
                         _ => {}
                     }
                     continue;
                 }
-
-                // Handle dispatch confirmation popup
-                if state.ui.show_confirm_dispatch {
-                    match key.code {
-                        KeyCode::Char('y') | KeyCode::Char('Y') => {
-                            state.ui.show_confirm_dispatch = false;
-                            if let Err(e) = state.run_workflow() {
-                                state.ui.output = Some(format!("Error dispatching workflow: {}", e));
-                                state.ui.output_is_error = true;
-                            } else {
-                                state.ui.output_is_error = false;
-                            }
-                        }
-                        _ => {
-                            state.ui.show_confirm_dispatch = false;
-                            state.ui.output = Some("Dispatch cancelled.".to_string());
-                            state.ui.output_is_error = false;
+                match key.code {
+                    KeyCode::Esc => state.ui.repo_import = None,
+                    KeyCode::Enter => {
+                        if let Err(e) = state.import_checked_repos() {
+                            state.ui.output = Some(format!("Error importing repos: {}", e));
+                            state.ui.output_is_error = true;
                         }
                     }
-                    continue;
+                    KeyCode::Up => select_previous(&mut import.list_state, import.filtered.len()),
+                    KeyCode::Down => select_next(&mut import.list_state, import.filtered.len()),
+                    KeyCode::Char('k') if ctrl => select_previous(&mut import.list_state, import.filtered.len()),
+                    KeyCode::Char('j') if ctrl => select_next(&mut import.list_state, import.filtered.len()),
+                    KeyCode::Char('a') if ctrl => state.toggle_import_check_all(),
+                    KeyCode::Char(' ') => state.toggle_import_check(),
+                    KeyCode::Backspace => {
+                        import.filter.pop();
+                        state.update_import_filter();
+                    }
+                    KeyCode::Char(c) => {
+                        import.filter.push(c);
+                        state.update_import_filter();
+                    }
+                    _ => {}
                 }
+                continue;
+            }
 
-                // Handle post-dispatch log prompt
-                if state.ui.awaiting_log_prompt {
-                    match key.code {
-                        KeyCode::Char('l') | KeyCode::Char('L') => {
-                            // Fetch logs but keep prompt active for retry
-                            if let Err(e) = state.watch_workflow_logs() {
-                                state.ui.output = Some(format!("Error fetching logs: {}\n\nPress 'l' to retry, 'v' to open in browser, or any other key to dismiss.", e));
-                                state.ui.output_is_error = true;
-                            } else {
-                                state.ui.output_is_error = false;
-                            }
-                        }
-                        KeyCode::Char('v') => {
-                            if let Err(e) = state.open_run_in_browser() {
-                                state.ui.output = Some(format!("Error opening browser: {}", e));
-                                state.ui.output_is_error = true;
-                            }
-                            state.ui.awaiting_log_prompt = false;
-                        }
-                        _ => {
-                            state.ui.awaiting_log_prompt = false;
+            // Handle the command palette
+            if let Some(palette) = state.ui.command_palette.as_mut() {
+                let ctrl = key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL);
+                match key.code {
+                    KeyCode::Esc => state.ui.command_palette = None,
+                    KeyCode::Enter => {
+                        if let Some(command) = state.take_palette_command()
+                            && run_palette_command(state, command) {
+                            break;
                         }
                     }
-                    continue;
+                    KeyCode::Up => select_previous(&mut palette.list_state, palette.filtered.len()),
+                    KeyCode::Down => select_next(&mut palette.list_state, palette.filtered.len()),
+                    KeyCode::Char('k' | 'p') if ctrl => select_previous(&mut palette.list_state, palette.filtered.len()),
+                    KeyCode::Char('j' | 'n') if ctrl => select_next(&mut palette.list_state, palette.filtered.len()),
+                    KeyCode::Backspace => {
+                        palette.query.pop();
+                        state.update_palette_filter();
+                    }
+                    KeyCode::Char(c) if !ctrl => {
+                        palette.query.push(c);
+                        state.update_palette_filter();
+                    }
+                    _ => {}
                 }
+                continue;
+            }
 
-                // Handle inputs popup
-                if state.ui.show_inputs_popup {
-                    // Tab cycles choice options regardless of editing state
-                    if matches!(key.code, KeyCode::Tab) {
-                        if let Some(field) = state.data.input_fields.get_mut(state.ui.input_fields_selected) {
-                            if field.input_type == "choice" && !field.options.is_empty() {
-                                let current_idx = field.options.iter().position(|o| o == &field.value);
-                                let next_idx = match current_idx {
-                                    Some(i) => (i + 1) % field.options.len(),
-                                    None => 0,
-                                };
-                                field.value = field.options[next_idx].clone();
-                            }
-                        }
-                        continue;
+            // Handle repo removal confirmation
+            if state.ui.confirm_remove_repo.is_some() {
+                if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                    if let Err(e) = state.remove_repo() {
+                        state.ui.output = Some(format!("Error removing repo: {}", e));
+                        state.ui.output_is_error = true;
                     }
-                    // BackTab cycles choice options backwards
-                    if matches!(key.code, KeyCode::BackTab) {
-                        if let Some(field) = state.data.input_fields.get_mut(state.ui.input_fields_selected) {
-                            if field.input_type == "choice" && !field.options.is_empty() {
-                                let current_idx = field.options.iter().position(|o| o == &field.value);
-                                let next_idx = match current_idx {
-                                    Some(0) | None => field.options.len() - 1,
-                                    Some(i) => i - 1,
-                                };
-                                field.value = field.options[next_idx].clone();
-                            }
+                } else {
+                    state.ui.confirm_remove_repo = None;
+                }
+                continue;
+            }
+
+            // Handle the repo alias/group popup
+            if let Some(edit) = state.ui.repo_edit.as_mut() {
+                match key.code {
+                    KeyCode::Esc => state.ui.repo_edit = None,
+                    KeyCode::Enter => {
+                        if let Err(e) = state.finish_repo_edit() {
+                            state.ui.output = Some(format!("Error saving repo: {}", e));
+                            state.ui.output_is_error = true;
                         }
-                        continue;
                     }
+                    KeyCode::Backspace => {
+                        edit.value.pop();
+                    }
+                    KeyCode::Char(c) => edit.value.push(c),
+                    _ => {}
+                }
+                continue;
+            }
 
-                    match key.code {
-                        KeyCode::Esc => {
-                            if state.ui.input_fields_editing {
-                                state.ui.input_fields_editing = false;
-                            } else {
-                                state.ui.show_inputs_popup = false;
-                            }
-                        }
-                        KeyCode::Char('j') | KeyCode::Down if !state.ui.input_fields_editing => {
-                            if !state.data.input_fields.is_empty() {
-                                state.ui.input_fields_selected =
-                                    (state.ui.input_fields_selected + 1) % state.data.input_fields.len();
-                            }
-                        }
-                        KeyCode::Char('k') | KeyCode::Up if !state.ui.input_fields_editing => {
-                            if !state.data.input_fields.is_empty() {
-                                if state.ui.input_fields_selected == 0 {
-                                    state.ui.input_fields_selected = state.data.input_fields.len() - 1;
-                                } else {
-                                    state.ui.input_fields_selected -= 1;
-                                }
-                            }
-                        }
-                        KeyCode::Enter if !state.ui.input_fields_editing => {
-                            state.ui.input_fields_editing = true;
-                        }
-                        KeyCode::Enter if state.ui.input_fields_editing => {
-                            state.ui.input_fields_editing = false;
+            // Handle protected dispatch confirmation: the phrase must be typed out
+            if state.ui.show_confirm_dispatch && let Some(guard) = state.ui.dispatch_guard.clone() {
+                match key.code {
+                    _ if guard.blocked.is_some() || key.code == KeyCode::Esc => state.cancel_dispatch(),
+                    KeyCode::Enter if state.ui.confirm_input == guard.phrase => confirm_dispatch(state),
+                    KeyCode::Backspace => {
+                        state.ui.confirm_input.pop();
+                    }
+                    KeyCode::Char(c) => {
+                        state.ui.confirm_input.push(c);
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            // Handle dispatch confirmation popup
+            if state.ui.show_confirm_dispatch {
+                match key.code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') => confirm_dispatch(state),
+                    _ => state.cancel_dispatch(),
+                }
+                continue;
+            }
+
+            // Handle failures popup
+            if state.ui.show_failures_popup {
+                match key.code {
+                    KeyCode::Esc => {
+                        state.ui.show_failures_popup = false;
+                    }
+                    KeyCode::Char('j') | KeyCode::Down => {
+                        select_next(&mut state.ui.failures_state, state.data.run_failures.len());
+                    }
+                    KeyCode::Char('k') | KeyCode::Up => {
+                        select_previous(&mut state.ui.failures_state, state.data.run_failures.len());
+                    }
+                    KeyCode::Enter => {
+                        if let Err(e) = state.jump_to_failure() {
+                            state.ui.show_failures_popup = false;
+                            state.ui.output = Some(format!("Error jumping to failure: {}", e));
+                            state.ui.output_is_error = true;
                         }
-                        KeyCode::Char('D') if !state.ui.input_fields_editing => {
-                            // Shift+D: show confirmation before dispatch
-                            state.ui.input_fields_editing = false;
-                            match state.build_dispatch_command() {
-                                Ok((_args, preview)) => {
-                                    state.ui.dispatch_command_preview = preview;
-                                    state.ui.show_inputs_popup = false;
-                                    state.ui.show_confirm_dispatch = true;
-                                }
-                                Err(e) => {
-                                    state.ui.output = Some(format!("Error: {}", e));
-                                    state.ui.output_is_error = true;
-                                    state.ui.show_inputs_popup = false;
-                                }
-                            }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            // Handle post-dispatch log prompt
+            if state.ui.awaiting_log_prompt {
+                match state.keymap.action(Scope::LogPrompt, &key) {
+                    Some(Action::WatchLogs) => {
+                        // Fetch logs but keep prompt active for retry
+                        if let Err(e) = state.watch_workflow_logs() {
+                            state.ui.output = Some(format!("Error fetching logs: {}\n\n{}", e, state.log_prompt_hint("retry", "dismiss")));
+                            state.ui.output_is_error = true;
+                        } else {
+                            state.ui.output_is_error = false;
                         }
-                        KeyCode::Char('S') if !state.ui.input_fields_editing => {
-                            // Shift+S: save current inputs as a replay
-                            match state.save_replay() {
-                                Ok(()) => {
-                                    state.ui.output_is_error = false;
-                                }
-                                Err(e) => {
-                                    state.ui.output = Some(format!("Error saving replay: {}", e));
-                                    state.ui.output_is_error = true;
-                                }
-                            }
+                    }
+                    Some(Action::OpenInBrowser) => {
+                        if let Err(e) = state.open_run_in_browser() {
+                            state.ui.output = Some(format!("Error opening browser: {}", e));
+                            state.ui.output_is_error = true;
                         }
-                        KeyCode::Backspace if state.ui.input_fields_editing => {
-                            if let Some(field) = state.data.input_fields.get_mut(state.ui.input_fields_selected) {
-                                if field.input_type != "choice" {
-                                    field.value.pop();
-                                }
-                            }
+                        state.ui.awaiting_log_prompt = false;
+                    }
+                    Some(Action::Failures) => {
+                        // Browse failures but keep prompt active for refresh
+                        state.open_failures();
+                    }
+                    _ => {
+                        state.ui.awaiting_log_prompt = false;
+                    }
+                }
+                continue;
+            }
+
+            // Handle inputs popup
+            if state.ui.show_inputs_popup {
+                // Tab accepts a value suggestion while typing, and cycles
+                // choice or sourced options regardless of editing state
+                if matches!(key.code, KeyCode::Tab) {
+                    if state.ui.input_fields_editing && state.accept_input_suggestion() {
+                        continue;
+                    }
+                    if let Some(field) = state.data.input_fields.get_mut(state.ui.input_fields_selected)
+                        && field.has_options() {
+                        let current_idx = field.options.iter().position(|o| o == &field.value);
+                        let next_idx = match current_idx {
+                            Some(i) => (i + 1) % field.options.len(),
+                            None => 0,
+                        };
+                        field.set_value(field.options[next_idx].clone());
+                        if state.ui.input_fields_editing {
+                            state.ui.input_editor.set_value(field.value.clone());
                         }
-                        KeyCode::Char(c) if state.ui.input_fields_editing => {
-                            if let Some(field) = state.data.input_fields.get_mut(state.ui.input_fields_selected) {
-                                if field.input_type == "boolean" {
-                                    field.value = if field.value == "true" {
-                                        "false".to_string()
-                                    } else {
-                                        "true".to_string()
-                                    };
-                                } else if field.input_type != "choice" {
-                                    field.value.push(c);
-                                }
-                            }
+                    }
+                    continue;
+                }
+                // BackTab cycles options backwards
+                if matches!(key.code, KeyCode::BackTab) {
+                    if let Some(field) = state.data.input_fields.get_mut(state.ui.input_fields_selected)
+                        && field.has_options() {
+                        let current_idx = field.options.iter().position(|o| o == &field.value);
+                        let next_idx = match current_idx {
+                            Some(0) | None => field.options.len() - 1,
+                            Some(i) => i - 1,
+                        };
+                        field.set_value(field.options[next_idx].clone());
+                        if state.ui.input_fields_editing {
+                            state.ui.input_editor.set_value(field.value.clone());
                         }
-                        _ => {}
                     }
                     continue;
                 }
 
-                // Handle replays popup
-                if state.ui.show_replays_popup {
-                    match key.code {
-                        KeyCode::Esc => {
-                            state.ui.show_replays_popup = false;
-                        }
-                        KeyCode::Char('j') | KeyCode::Down => {
-                            if !state.data.replays_list.is_empty() {
-                                let sel = state.ui.replays_state.selected().unwrap_or(0);
-                                state.ui.replays_state.select(Some((sel + 1) % state.data.replays_list.len()));
-                            }
-                        }
-                        KeyCode::Char('k') | KeyCode::Up => {
-                            if !state.data.replays_list.is_empty() {
-                                let sel = state.ui.replays_state.selected().unwrap_or(0);
-                                if sel == 0 {
-                                    state.ui.replays_state.select(Some(state.data.replays_list.len() - 1));
-                                } else {
-                                    state.ui.replays_state.select(Some(sel - 1));
-                                }
-                            }
-                        }
-                        KeyCode::Enter => {
-                            match state.run_replay() {
-                                Ok(()) => {
-                                    state.ui.output_is_error = false;
-                                }
-                                Err(e) => {
-                                    state.ui.show_replays_popup = false;
-                                    state.ui.output = Some(format!("Error running replay: {}", e));
-                                    state.ui.output_is_error = true;
-                                }
+                // Configured keys only apply while not typing into a field
+                let action = if state.ui.input_fields_editing {
+                    None
+                } else {
+                    state.keymap.action(Scope::Inputs, &key)
+                };
+                match key.code {
+                    _ if action == Some(Action::Dispatch) => {
+                        // Show confirmation before dispatch, or stay on the
+                        // input GitHub would reject
+                        if state.report_input_problems() {
+                            continue;
+                        }
+                        match state.build_dispatch_command() {
+                            Ok((_args, preview)) => {
+                                state.show_dispatch_confirmation(preview);
+                                state.ui.show_inputs_popup = false;
                             }
-                        }
-                        KeyCode::Char('d') => {
-                            if let Err(e) = state.delete_replay() {
-                                state.ui.output = Some(format!("Error deleting replay: {}", e));
+                            Err(e) => {
+                                state.ui.output = Some(format!("Error: {}", e));
                                 state.ui.output_is_error = true;
+                                state.ui.show_inputs_popup = false;
                             }
                         }
-                        _ => {}
                     }
-                    continue;
-                }
-
-                // Handle fuzzy search input
-                if state.ui.search_active {
-                    match key.code {
-                        KeyCode::Esc => {
-                            // Cancel search, restore full list
-                            state.reset_search();
-                            match state.ui.focus {
-                                Focus::Repo => state.ui.repos_state.select(
-                                    if state.data.repos.is_empty() { None } else { Some(0) }
-                                ),
-                                Focus::Branches => state.ui.branches_state.select(
-                                    if state.data.branches.is_empty() { None } else { Some(0) }
-                                ),
-                                Focus::Workflows => state.ui.workflows_state.select(
-                                    if state.data.workflows.is_empty() { None } else { Some(0) }
-                                ),
-                                _ => {}
+                    _ if action == Some(Action::SaveReplay) => {
+                        // Save current inputs as a replay
+                        match state.save_replay() {
+                            Ok(()) => {
+                                state.ui.output_is_error = false;
                             }
-                        }
-                        KeyCode::Enter => {
-                            // Confirm search, keep filter active
-                            state.ui.search_active = false;
-                        }
-                        KeyCode::Backspace => {
-                            state.ui.search_query.pop();
-                            state.update_search_filter();
-                        }
-                        KeyCode::Up | KeyCode::Char('k') if key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) => {
-                            match state.ui.focus {
-                                Focus::Repo => select_previous(&mut state.ui.repos_state, state.ui.filtered_repo_indices.len()),
-                                Focus::Branches => select_previous(&mut state.ui.branches_state, state.ui.filtered_branch_indices.len()),
-                                Focus::Workflows => select_previous(&mut state.ui.workflows_state, state.ui.filtered_workflow_indices.len()),
-                                _ => {}
+                            Err(e) => {
+                                state.ui.output = Some(format!("Error saving replay: {}", e));
+                                state.ui.output_is_error = true;
                             }
                         }
-                        KeyCode::Down | KeyCode::Char('j') if key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) => {
-                            match state.ui.focus {
-                                Focus::Repo => select_next(&mut state.ui.repos_state, state.ui.filtered_repo_indices.len()),
-                                Focus::Branches => select_next(&mut state.ui.branches_state, state.ui.filtered_branch_indices.len()),
-                                Focus::Workflows => select_next(&mut state.ui.workflows_state, state.ui.filtered_workflow_indices.len()),
-                                _ => {}
-                            }
+                    }
+                    _ if action == Some(Action::UnsetInput) => {
+                        if let Some(field) = state.data.input_fields.get_mut(state.ui.input_fields_selected) {
+                            field.unset();
                         }
-                        KeyCode::Up => {
-                            match state.ui.focus {
-                                Focus::Repo => select_previous(&mut state.ui.repos_state, state.ui.filtered_repo_indices.len()),
-                                Focus::Branches => select_previous(&mut state.ui.branches_state, state.ui.filtered_branch_indices.len()),
-                                Focus::Workflows => select_previous(&mut state.ui.workflows_state, state.ui.filtered_workflow_indices.len()),
-                                _ => {}
-                            }
+                        state.keep_input_selection_shown();
+                    }
+                    _ if action == Some(Action::ResetInputs) => state.reset_inputs(),
+                    _ if action == Some(Action::SortInputs) => state.cycle_input_sort(),
+                    _ if action == Some(Action::EditInEditor) => {
+                        if let Err(e) = edit_input_in_editor(&mut terminal, state) {
+                            state.ui.output = Some(format!("Error editing input: {}", e));
+                            state.ui.output_is_error = true;
                         }
-                        KeyCode::Down => {
-                            match state.ui.focus {
-                                Focus::Repo => select_next(&mut state.ui.repos_state, state.ui.filtered_repo_indices.len()),
-                                Focus::Branches => select_next(&mut state.ui.branches_state, state.ui.filtered_branch_indices.len()),
-                                Focus::Workflows => select_next(&mut state.ui.workflows_state, state.ui.filtered_workflow_indices.len()),
-                                _ => {}
-                            }
+                    }
+                    KeyCode::Esc => {
+                        if state.ui.input_fields_editing {
+                            state.ui.input_fields_editing = false;
+                        } else {
+                            state.ui.show_inputs_popup = false;
                         }
-                        KeyCode::Char(c) => {
-                            state.ui.search_query.push(c);
-                            state.update_search_filter();
+                    }
+                    KeyCode::Char('j') | KeyCode::Down if !state.ui.input_fields_editing => {
+                        state.move_input_selection(true, true);
+                    }
+                    KeyCode::Char('k') | KeyCode::Up if !state.ui.input_fields_editing => {
+                        state.move_input_selection(false, true);
+                    }
+                    KeyCode::Enter if !state.ui.input_fields_editing => state.start_input_edit(),
+                    KeyCode::Enter if state.ui.input_fields_editing => {
+                        state.ui.input_fields_editing = false;
+                    }
+                    KeyCode::Down | KeyCode::Up if state.ui.input_fields_editing => {
+                        let count = state.input_suggestions().len();
+                        if count > 0 {
+                            let step = if key.code == KeyCode::Down { 1 } else { count - 1 };
+                            state.ui.input_suggestion = (state.ui.input_suggestion + step) % count;
                         }
-                        _ => {}
                     }
-                    continue;
+                    _ if state.ui.input_fields_editing => state.edit_input_value(&key),
+                    _ => {}
                 }
+                continue;
+            }
 
+            // Handle replays popup
+            if state.ui.show_replays_popup {
+                let action = state.keymap.action(Scope::Replays, &key);
                 match key.code {
-                    KeyCode::Esc | KeyCode::Char('q') => break,
-                    KeyCode::Char('?') => {
-                        state.ui.show_help_popup = !state.ui.show_help_popup;
+                    _ if action == Some(Action::DeleteReplay) => {
+                        if let Err(e) = state.delete_replay() {
+                            state.ui.output = Some(format!("Error deleting replay: {}", e));
+                            state.ui.output_is_error = true;
+                        }
                     }
-                    KeyCode::Char('a') => {
-                        state.ui.show_add_repo_popup = true;
-                        state.ui.add_repo_focus_owner = true;
+                    KeyCode::Esc => {
+                        state.ui.show_replays_popup = false;
                     }
-                    KeyCode::Char('v') => {
-                        if let Err(e) = state.open_repo_in_browser() {
-                            state.ui.output = Some(format!("Error opening browser: {}", e));
-                            state.ui.output_is_error = true;
+                    KeyCode::Char('j') | KeyCode::Down
+                        if !state.data.replays_list.is_empty() => {
+                        let sel = state.ui.replays_state.selected().unwrap_or(0);
+                        state.ui.replays_state.select(Some((sel + 1) % state.data.replays_list.len()));
+                    }
+                    KeyCode::Char('k') | KeyCode::Up
+                        if !state.data.replays_list.is_empty() => {
+                        let sel = state.ui.replays_state.selected().unwrap_or(0);
+                        if sel == 0 {
+                            state.ui.replays_state.select(Some(state.data.replays_list.len() - 1));
+                        } else {
+                            state.ui.replays_state.select(Some(sel - 1));
                         }
                     }
-                    KeyCode::Char('i') => {
-                        if !state.data.input_fields.is_empty() {
-                            state.ui.show_inputs_popup = true;
-                            state.ui.input_fields_selected = 0;
-                            state.ui.input_fields_editing = false;
-                        } else if !state.data.workflows.is_empty() {
-                            // No inputs, but workflow selected — show dispatch confirmation directly
-                            match state.build_dispatch_command() {
-                                Ok((_args, preview)) => {
-                                    state.ui.dispatch_command_preview = preview;
-                                    state.ui.show_confirm_dispatch = true;
-                                }
-                                Err(e) => {
-                                    state.ui.output = Some(format!("Error: {}", e));
-                                    state.ui.output_is_error = true;
-                                }
+                    KeyCode::Enter => {
+                        match state.request_replay() {
+                            Ok(()) => {
+                                state.ui.output_is_error = false;
+                            }
+                            Err(e) => {
+                                state.ui.show_replays_popup = false;
+                                state.ui.output = Some(format!("Error running replay: {}", e));
+                                state.ui.output_is_error = true;
                             }
                         }
                     }
-                    KeyCode::Char('/') => {
-                        // Activate fuzzy search for the focused panel
-                        if matches!(state.ui.focus, Focus::Repo | Focus::Branches | Focus::Workflows) {
-                            state.ui.search_active = true;
-                            state.ui.search_query.clear();
+                    _ => {}
+                }
+                continue;
+            }
+
+            // Handle fuzzy search input
+            if state.ui.search_active {
+                match key.code {
+                    KeyCode::Esc => {
+                        // Cancel search, restore full list
+                        state.reset_search();
+                        match state.ui.focus {
+                            Focus::Repo => state.ui.repos_state.select(
+                                if state.data.repos.is_empty() { None } else { Some(0) }
+                            ),
+                            Focus::Branches => state.ui.branches_state.select(
+                                if state.data.branches.is_empty() { None } else { Some(0) }
+                            ),
+                            Focus::Workflows => state.ui.workflows_state.select(
+                                if state.data.workflows.is_empty() { None } else { Some(0) }
+                            ),
+                            _ => {}
                         }
                     }
-                    KeyCode::Char('r') => {
-                        // Open replays popup for the selected repo
-                        state.open_replays();
+                    KeyCode::Enter => {
+                        // Confirm search, keep filter active
+                        state.ui.search_active = false;
                     }
-                    KeyCode::Left | KeyCode::Char('h') => {
-                        if matches!(state.ui.focus, Focus::Repo) {
-                            state.ui.repos_hscroll = state.ui.repos_hscroll.saturating_sub(1);
+                    KeyCode::Up | KeyCode::Char('k') if key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) => {
+                        match state.ui.focus {
+                            Focus::Repo => select_previous(&mut state.ui.repos_state, state.ui.repo_rows.len()),
+                            Focus::Branches => select_previous(&mut state.ui.branches_state, state.ui.filtered_branch_indices.len()),
+                            Focus::Workflows => select_previous(&mut state.ui.workflows_state, state.ui.filtered_workflow_indices.len()),
+                            _ => {}
                         }
                     }
-                    KeyCode::Right | KeyCode::Char('l') => {
-                        if matches!(state.ui.focus, Focus::Repo) {
-                            state.ui.repos_hscroll = state.ui.repos_hscroll.saturating_add(1);
+                    KeyCode::Down | KeyCode::Char('j') if key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) => {
+                        match state.ui.focus {
+                            Focus::Repo => select_next(&mut state.ui.repos_state, state.ui.repo_rows.len()),
+                            Focus::Branches => select_next(&mut state.ui.branches_state, state.ui.filtered_branch_indices.len()),
+                            Focus::Workflows => select_next(&mut state.ui.workflows_state, state.ui.filtered_workflow_indices.len()),
+                            _ => {}
                         }
                     }
-                    KeyCode::Char('j') | KeyCode::Down => {
-                        // Move down in the current focused list
+                    KeyCode::Up => {
                         match state.ui.focus {
-                            Focus::Repo => select_next(&mut state.ui.repos_state, state.ui.filtered_repo_indices.len()),
-                            Focus::Branches => {
-                                select_next(&mut state.ui.branches_state, state.ui.filtered_branch_indices.len())
-                            }
-                            Focus::Workflows => {
-                                select_next(&mut state.ui.workflows_state, state.ui.filtered_workflow_indices.len())
-                            }
-                            Focus::Inputs => {
-                                select_next(&mut state.ui.inputs_state, state.data.inputs.len())
-                            }
-                            Focus::Output => {
-                                state.ui.output_scroll = state.ui.output_scroll.saturating_add(1);
-                            }
+                            Focus::Repo => select_previous(&mut state.ui.repos_state, state.ui.repo_rows.len()),
+                            Focus::Branches => select_previous(&mut state.ui.branches_state, state.ui.filtered_branch_indices.len()),
+                            Focus::Workflows => select_previous(&mut state.ui.workflows_state, state.ui.filtered_workflow_indices.len()),
+                            _ => {}
                         }
                     }
-                    KeyCode::Char('k') | KeyCode::Up => {
-                        // Move up in the current focused list
+                    KeyCode::Down => {
                         match state.ui.focus {
-                            Focus::Repo => {
-                                select_previous(&mut state.ui.repos_state, state.ui.filtered_repo_indices.len())
-                            }
-                            Focus::Branches => {
-                                select_previous(&mut state.ui.branches_state, state.ui.filtered_branch_indices.len())
-                            }
-                            Focus::Workflows => {
-                                select_previous(&mut state.ui.workflows_state, state.ui.filtered_workflow_indices.len())
-                            }
-                            Focus::Inputs => {
-                                select_previous(&mut state.ui.inputs_state, state.data.inputs.len())
-                            }
-                            Focus::Output => {
-                                state.ui.output_scroll = state.ui.output_scroll.saturating_sub(1);
-                            }
+                            Focus::Repo => select_next(&mut state.ui.repos_state, state.ui.repo_rows.len()),
+                            Focus::Branches => select_next(&mut state.ui.branches_state, state.ui.filtered_branch_indices.len()),
+                            Focus::Workflows => select_next(&mut state.ui.workflows_state, state.ui.filtered_workflow_indices.len()),
+                            _ => {}
                         }
                     }
-                    KeyCode::Tab => {
-                        // Cycle through focus areas
-                        state.ui.focus = match state.ui.focus {
-                            Focus::Repo => Focus::Branches,
-                            Focus::Branches => Focus::Workflows,
-                            Focus::Workflows => Focus::Inputs,
-                            Focus::Inputs => Focus::Output,
-                            Focus::Output => Focus::Repo,
-                        };
+                    _ => {
+                        if state.ui.search_query.handle_key(&key) {
+                            state.update_search_filter();
+                        }
                     }
-                    KeyCode::BackTab => {
-                        // Cycle backwards through focus areas
-                        state.ui.focus = match state.ui.focus {
-                            Focus::Repo => Focus::Output,
-                            Focus::Branches => Focus::Repo,
-                            Focus::Workflows => Focus::Branches,
-                            Focus::Inputs => Focus::Workflows,
-                            Focus::Output => Focus::Inputs,
-                        };
+                }
+                continue;
+            }
+
+            // Repo management keys only apply while the Repos panel has focus
+            if let Some(action) = state.keymap.action(Scope::Main, &key)
+                && (!action.repos_panel_only() || matches!(state.ui.focus, Focus::Repo))
+                && perform_action(state, action) {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Run a key-bound action outside of any popup. Returns true to quit.
+fn perform_action(state: &mut AppState, action: Action) -> bool {
+    match action {
+        Action::Quit => return true,
+        Action::Help => {
+            state.ui.show_help_popup = !state.ui.show_help_popup;
+        }
+        Action::AddRepo => {
+            state.open_add_repo();
+        }
+        Action::ImportRepos => {
+            state.open_repo_import();
+        }
+        Action::OpenInBrowser => {
+            if let Err(e) = state.open_repo_in_browser() {
+                state.ui.output = Some(format!("Error opening browser: {}", e));
+                state.ui.output_is_error = true;
+            }
+        }
+        Action::EditInputs => {
+            if !state.data.input_fields.is_empty() {
+                state.ui.show_inputs_popup = true;
+                state.ui.input_fields_selected = 0;
+                state.ui.input_fields_editing = false;
+                state.keep_input_selection_shown();
+            } else if !state.data.workflows.is_empty() {
+                // No inputs, but workflow selected — show dispatch confirmation directly
+                match state.build_dispatch_command() {
+                    Ok((_args, preview)) => {
+                        state.show_dispatch_confirmation(preview);
                     }
-                    KeyCode::Enter => {
-                        // Handle selection based on current focus
-                        match state.ui.focus {
-                            Focus::Repo => {
-                                if let Err(e) = state.load_branches() {
-                                    state.ui.output = Some(format!("Error loading branches: {}", e));
-                                    state.ui.output_is_error = true;
-                                }
-                                state.ui.focus = Focus::Branches;
-                            }
-                            Focus::Branches => {
-                                if let Err(e) = state.load_workflows() {
-                                    state.ui.output = Some(format!("Error loading workflows: {}", e));
-                                    state.ui.output_is_error = true;
-                                }
-                                state.ui.focus = Focus::Workflows;
-                            }
-                            Focus::Workflows => {
-                                if let Err(e) = state.load_inputs() {
-                                    state.ui.output = Some(format!("Error loading inputs: {}", e));
-                                    state.ui.output_is_error = true;
-                                }
-                                state.ui.focus = Focus::Inputs;
-                            }
-                            Focus::Inputs => {
-                                // Show dispatch confirmation popup
-                                match state.build_dispatch_command() {
-                                    Ok((_args, preview)) => {
-                                        state.ui.dispatch_command_preview = preview;
-                                        state.ui.show_confirm_dispatch = true;
-                                    }
-                                    Err(e) => {
-                                        state.ui.output = Some(format!("Error: {}", e));
-                                        state.ui.output_is_error = true;
-                                        state.ui.focus = Focus::Output;
-                                    }
-                                }
-                            }
-                            Focus::Output => {}
+                    Err(e) => {
+                        state.ui.output = Some(format!("Error: {}", e));
+                        state.ui.output_is_error = true;
+                    }
+                }
+            }
+        }
+        Action::Search => {
+            // Activate fuzzy search for the focused panel
+            if matches!(state.ui.focus, Focus::Repo | Focus::Branches | Focus::Workflows) {
+                state.ui.search_active = true;
+                state.ui.search_query.clear();
+            }
+        }
+        Action::Replays => {
+            // Open replays popup for the selected repo
+            state.open_replays();
+        }
+        Action::RemoveRepo
+        | Action::MoveRepoDown
+        | Action::MoveRepoUp
+        | Action::ToggleFavorite
+        | Action::SetAlias
+        | Action::SetGroup => {
+            // Repo management
+            let result = match action {
+                Action::RemoveRepo => state.request_remove_repo(),
+                Action::MoveRepoDown => state.move_selected_repo(true),
+                Action::MoveRepoUp => state.move_selected_repo(false),
+                Action::ToggleFavorite => state.toggle_favorite(),
+                Action::SetAlias => state.start_repo_edit(RepoField::Alias),
+                _ => state.start_repo_edit(RepoField::Group),
+            };
+            if let Err(e) = result {
+                state.ui.output = Some(format!("Error: {}", e));
+                state.ui.output_is_error = true;
+            }
+        }
+        Action::Failures => {
+            // Open the failures of the last fetched run
+            state.open_failures();
+        }
+        Action::ScrollLeft => {
+            if matches!(state.ui.focus, Focus::Repo) {
+                state.ui.repos_hscroll = state.ui.repos_hscroll.saturating_sub(1);
+            }
+        }
+        Action::ScrollRight => {
+            if matches!(state.ui.focus, Focus::Repo) {
+                state.ui.repos_hscroll = state.ui.repos_hscroll.saturating_add(1);
+            }
+        }
+        Action::Down => {
+            // Move down in the current focused list
+            match state.ui.focus {
+                Focus::Repo => select_next(&mut state.ui.repos_state, state.ui.repo_rows.len()),
+                Focus::Branches => {
+                    select_next(&mut state.ui.branches_state, state.ui.filtered_branch_indices.len())
+                }
+                Focus::Workflows => {
+                    select_next(&mut state.ui.workflows_state, state.ui.filtered_workflow_indices.len())
+                }
+                Focus::Inputs => {
+                    select_next(&mut state.ui.inputs_state, state.data.inputs.len())
+                }
+                Focus::Output => {
+                    state.ui.output_scroll = state.ui.output_scroll.saturating_add(1);
+                }
+            }
+        }
+        Action::Up => {
+            // Move up in the current focused list
+            match state.ui.focus {
+                Focus::Repo => {
+                    select_previous(&mut state.ui.repos_state, state.ui.repo_rows.len())
+                }
+                Focus::Branches => {
+                    select_previous(&mut state.ui.branches_state, state.ui.filtered_branch_indices.len())
+                }
+                Focus::Workflows => {
+                    select_previous(&mut state.ui.workflows_state, state.ui.filtered_workflow_indices.len())
+                }
+                Focus::Inputs => {
+                    select_previous(&mut state.ui.inputs_state, state.data.inputs.len())
+                }
+                Focus::Output => {
+                    state.ui.output_scroll = state.ui.output_scroll.saturating_sub(1);
+                }
+            }
+        }
+        Action::NextPanel => {
+            // Cycle through focus areas
+            state.ui.focus = match state.ui.focus {
+                Focus::Repo => Focus::Branches,
+                Focus::Branches => Focus::Workflows,
+                Focus::Workflows => Focus::Inputs,
+                Focus::Inputs => Focus::Output,
+                Focus::Output => Focus::Repo,
+            };
+        }
+        Action::PreviousPanel => {
+            // Cycle backwards through focus areas
+            state.ui.focus = match state.ui.focus {
+                Focus::Repo => Focus::Output,
+                Focus::Branches => Focus::Repo,
+                Focus::Workflows => Focus::Branches,
+                Focus::Inputs => Focus::Workflows,
+                Focus::Output => Focus::Inputs,
+            };
+        }
+        Action::Select => {
+            // Handle selection based on current focus
+            match state.ui.focus {
+                Focus::Repo => {
+                    // Enter on a group header expands or collapses it
+                    if state.toggle_selected_group() {
+                        return false;
+                    }
+                    if let Err(e) = state.load_branches() {
+                        state.ui.output = Some(format!("Error loading branches: {}", e));
+                        state.ui.output_is_error = true;
+                    }
+                    state.ui.focus = Focus::Branches;
+                }
+                Focus::Branches => {
+                    if let Err(e) = state.load_workflows() {
+                        state.ui.output = Some(format!("Error loading workflows: {}", e));
+                        state.ui.output_is_error = true;
+                    }
+                    state.ui.focus = Focus::Workflows;
+                }
+                Focus::Workflows => {
+                    if let Err(e) = state.load_inputs() {
+                        state.ui.output = Some(format!("Error loading inputs: {}", e));
+                        state.ui.output_is_error = true;
+                    }
+                    state.ui.focus = Focus::Inputs;
+                }
+                Focus::Inputs => {
+                    // Show dispatch confirmation popup
+                    if state.report_input_problems() {
+                        return false;
+                    }
+                    match state.build_dispatch_command() {
+                        Ok((_args, preview)) => {
+                            state.show_dispatch_confirmation(preview);
+                        }
+                        Err(e) => {
+                            state.ui.output = Some(format!("Error: {}", e));
+                            state.ui.output_is_error = true;
+                            state.ui.focus = Focus::Output;
                         }
                     }
-                    _ => {}
                 }
+                Focus::Output => {}
             }
         }
+        Action::CommandPalette => state.open_command_palette(),
+        Action::Dispatch if state.report_input_problems() => {}
+        Action::Dispatch => {
+            // Confirm the selected workflow with the current input values
+            match state.build_dispatch_command() {
+                Ok((_args, preview)) => state.show_dispatch_confirmation(preview),
+                Err(e) => {
+                    state.ui.output = Some(format!("Error: {}", e));
+                    state.ui.output_is_error = true;
+                }
+            }
+        }
+        Action::WatchLogs => {
+            if let Err(e) = state.watch_workflow_logs() {
+                state.ui.output = Some(format!("Error fetching logs: {}", e));
+                state.ui.output_is_error = true;
+            }
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Run the command picked in the command palette. Returns true to quit.
+fn run_palette_command(state: &mut AppState, command: PaletteCommand) -> bool {
+    let result = match command {
+        PaletteCommand::Action(action) => return perform_action(state, action),
+        PaletteCommand::SwitchRepo(idx) => state.switch_to_repo(idx).map_err(|e| format!("Error loading branches: {}", e)),
+        PaletteCommand::Replay(replay) => state.request_replay_of(&replay).map_err(|e| format!("Error running replay: {}", e)),
+        PaletteCommand::Refresh => state.load_branches().map_err(|e| format!("Error loading branches: {}", e)),
+        PaletteCommand::ViewRuns => state.view_recent_runs().map_err(|e| format!("Error listing runs: {}", e)),
+    };
+    if let Err(e) = result {
+        state.ui.output = Some(e);
+        state.ui.output_is_error = true;
+    }
+    false
+}
+
+/// Dispatch from the confirmation popup, reporting failures in the output panel.
+fn confirm_dispatch(state: &mut AppState) {
+    if let Err(e) = state.confirm_dispatch() {
+        state.ui.output = Some(format!("Error dispatching workflow: {}", e));
+        state.ui.output_is_error = true;
+    } else {
+        state.ui.output_is_error = false;
+    }
+}
+
+/// Lines the scroll wheel moves the Output panel by.
+const OUTPUT_SCROLL_STEP: u16 = 3;
+
+/// Handle a mouse event using the areas recorded by the last render.
+/// Returns true to quit.
+fn handle_mouse(state: &mut AppState, mouse: MouseEvent) -> bool {
+    let hit = |rect: ratatui::layout::Rect| rect.contains((mouse.column, mouse.row).into());
+    let areas = state.ui.hit_areas.clone();
+    let click = matches!(mouse.kind, MouseEventKind::Down(MouseButton::Left));
+    let scroll_down = matches!(mouse.kind, MouseEventKind::ScrollDown);
+    let scroll_up = matches!(mouse.kind, MouseEventKind::ScrollUp);
+
+    if state.ui.config_error.is_some() {
+        return false;
+    }
+    if state.ui.show_help_popup {
+        state.ui.show_help_popup = !click;
+        return false;
+    }
+
+    // Confirmation popup: its buttons confirm or cancel
+    if state.ui.show_confirm_dispatch {
+        if click && areas.confirm_button.is_some_and(hit) {
+            let can_confirm = match &state.ui.dispatch_guard {
+                Some(guard) => guard.blocked.is_none() && state.ui.confirm_input == guard.phrase,
+                None => true,
+            };
+            if can_confirm {
+                confirm_dispatch(state);
+            }
+        } else if click && areas.cancel_button.is_some_and(hit) {
+            state.cancel_dispatch();
+        }
+        return false;
+    }
+
+    // Inputs popup: click a field to select it or a choice option to pick it
+    if state.ui.show_inputs_popup {
+        if click {
+            if let Some(&(_, field, option)) = areas.input_options.iter().find(|(rect, _, _)| hit(*rect)) {
+                let value = state.data.input_fields[field].options[option].clone();
+                state.data.input_fields[field].set_value(value);
+                state.ui.input_fields_selected = field;
+                state.ui.input_fields_editing = false;
+            } else if let Some(&(_, field)) = areas.input_fields.iter().find(|(rect, _)| hit(*rect))
+                && field != state.ui.input_fields_selected {
+                state.ui.input_fields_selected = field;
+                state.ui.input_fields_editing = false;
+            }
+        } else if (scroll_down || scroll_up) && !state.ui.input_fields_editing {
+            state.move_input_selection(scroll_down, false);
+        }
+        return false;
+    }
+
+    // Other popups and prompts are keyboard-only
+    if state.ui.show_add_repo_popup
+        || state.ui.show_replays_popup
+        || state.ui.show_failures_popup
+        || state.ui.repo_import.is_some()
+        || state.ui.command_palette.is_some()
+        || state.ui.confirm_remove_repo.is_some()
+        || state.ui.repo_edit.is_some()
+        || state.ui.search_active
+    {
+        return false;
+    }
+
+    let (focus, area) = if hit(areas.repos) {
+        (Focus::Repo, areas.repos)
+    } else if hit(areas.branches) {
+        (Focus::Branches, areas.branches)
+    } else if hit(areas.workflows) {
+        (Focus::Workflows, areas.workflows)
+    } else if hit(areas.output) {
+        (Focus::Output, areas.output)
+    } else {
+        return false;
+    };
+
+    if scroll_down || scroll_up {
+        if matches!(focus, Focus::Output) {
+            state.ui.output_scroll = if scroll_down {
+                state.ui.output_scroll.saturating_add(OUTPUT_SCROLL_STEP)
+            } else {
+                state.ui.output_scroll.saturating_sub(OUTPUT_SCROLL_STEP)
+            };
+            return false;
+        }
+        // Scroll the list under the pointer, whichever panel has focus
+        let previous = std::mem::replace(&mut state.ui.focus, focus);
+        let quit = perform_action(state, if scroll_down { Action::Down } else { Action::Up });
+        state.ui.focus = previous;
+        return quit;
+    }
+    if !click {
+        return false;
+    }
+
+    // A click focuses the panel and selects the row under the pointer;
+    // clicking the selected row again opens it like Enter
+    let (list_state, len) = match focus {
+        Focus::Repo => (&mut state.ui.repos_state, state.ui.repo_rows.len()),
+        Focus::Branches => (&mut state.ui.branches_state, state.ui.filtered_branch_indices.len()),
+        Focus::Workflows => (&mut state.ui.workflows_state, state.ui.filtered_workflow_indices.len()),
+        _ => {
+            state.ui.focus = focus;
+            return false;
+        }
+    };
+    state.ui.focus = focus;
+    // Rows start inside the border
+    if mouse.row <= area.y || mouse.row >= area.bottom().saturating_sub(1) {
+        return false;
+    }
+    let row = list_state.offset() + (mouse.row - area.y - 1) as usize;
+    if row >= len {
+        return false;
+    }
+    if list_state.selected() == Some(row) {
+        return perform_action(state, Action::Select);
+    }
+    list_state.select(Some(row));
+    false
+}
+
+/// Edit the selected input's value in `$EDITOR` through a temp file, for
+/// multi-line values and JSON.
+fn edit_input_in_editor(terminal: &mut DefaultTerminal, state: &mut AppState) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(field) = state.data.input_fields.get(state.ui.input_fields_selected) else {
+        return Ok(());
+    };
+    if matches!(field.input_type.as_str(), "choice" | "boolean") {
+        return Err(format!("'{}' is a {} input and can't be edited as text", field.name, field.input_type).into());
+    }
+    let name: String = field.name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    // The extension lets the editor pick syntax highlighting
+    let extension = if field.is_json() { "json" } else { "txt" };
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    let path = std::env::temp_dir().join(format!("dispatchrr-{}-{}-{}.{}", std::process::id(), nanos, name, extension));
+    create_private_file(&path)?.write_all(state.input_editor_text().unwrap_or_default().as_bytes())?;
+
+    let edited = open_in_editor(terminal, &path).and_then(|()| std::fs::read_to_string(&path));
+    let _ = std::fs::remove_file(&path);
+    state.ui.input_fields_editing = false;
+    state.set_input_from_editor(&edited?)?;
+    Ok(())
+}
+
+/// Create a file only the current user can read. Fails when the path
+/// already exists, so a file planted in the shared temp dir, or a symlink
+/// there, is never written through.
+fn create_private_file(path: &Path) -> std::io::Result<std::fs::File> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+}
+
+/// Suspend the TUI, open `path` in `$VISUAL` / `$EDITOR` and restore the TUI
+/// once the editor exits.
+pub fn open_in_editor(terminal: &mut DefaultTerminal, path: &Path) -> std::io::Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+    // Allow editors with flags, e.g. EDITOR="code --wait"
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+
+    disable_raw_mode()?;
+    execute!(std::io::stdout(), DisableBracketedPaste, DisableMouseCapture, LeaveAlternateScreen)?;
+
+    let status = std::process::Command::new(program).args(parts).arg(path).status();
+
+    execute!(std::io::stdout(), EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste)?;
+    enable_raw_mode()?;
+    terminal.clear()?;
+
+    let status = status?;
+    if !status.success() {
+        return Err(std::io::Error::other(format!("{} exited with {}", program, status)));
     }
     Ok(())
 }
@@ -0,0 +1,257 @@
+use chrono::{DateTime, Datelike, Local, Timelike, Weekday};
+
+use crate::config::GuardrailConfig;
+
+/// The dispatch being checked against the guardrails.
+#[derive(Debug, Clone)]
+pub struct DispatchTarget {
+    pub repo: String,
+    pub workflow: String,
+    pub branch: String,
+    pub inputs: Vec<(String, String)>,
+}
+
+/// What the confirmation popup must enforce for a protected dispatch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuardCheck {
+    pub banner: String,          // red warning shown above the command
+    pub phrase: String,          // must be typed exactly to confirm
+    pub blocked: Option<String>, // why the dispatch is not allowed right now
+}
+
+/// Evaluate every rule against `target`; `None` when no rule matches.
+pub fn check(rules: &[GuardrailConfig], target: &DispatchTarget, now: DateTime<Local>) -> Option<GuardCheck> {
+    let matching: Vec<&GuardrailConfig> = rules.iter().filter(|r| matches(r, target)).collect();
+    if matching.is_empty() {
+        return None;
+    }
+
+    let phrase = matching
+        .iter()
+        .find_map(|r| r.confirm.clone())
+        .unwrap_or_else(|| target.repo.clone());
+
+    let mut banner_parts: Vec<String> = matching.iter().filter_map(|r| r.message.clone()).collect();
+    if banner_parts.is_empty() {
+        banner_parts.push(format!("Protected dispatch: {} → {} @ {}", target.repo, target.workflow, target.branch));
+    }
+
+    let blocked = matching.iter().find_map(|r| blocked_reason(r, now));
+
+    Some(GuardCheck {
+        banner: banner_parts.join(" | "),
+        phrase,
+        blocked,
+    })
+}
+
+fn matches(rule: &GuardrailConfig, target: &DispatchTarget) -> bool {
+    rule.repo.as_ref().is_none_or(|r| *r == target.repo)
+        && rule.workflow.as_ref().is_none_or(|w| *w == target.workflow)
+        && rule.branch.as_ref().is_none_or(|b| glob_match(b, &target.branch))
+        && rule.inputs.iter().all(|wanted| {
+            target
+                .inputs
+                .iter()
+                .any(|(name, value)| *name == wanted.name && *value == wanted.value)
+        })
+}
+
+fn blocked_reason(rule: &GuardrailConfig, now: DateTime<Local>) -> Option<String> {
+    let today = now.weekday();
+    if rule.blocked_days.iter().any(|d| parse_weekday(d) == Some(today)) {
+        return Some(format!("dispatches are blocked on {}", weekday_name(today)));
+    }
+    if let Some(window) = rule.allowed_hours {
+        let hour = now.hour();
+        if !window.contains(hour) {
+            return Some(format!(
+                "dispatches are only allowed between {:02}:00 and {:02}:00 (now {:02}:{:02})",
+                window.from, window.to, hour, now.minute()
+            ));
+        }
+    }
+    None
+}
+
+pub(crate) fn parse_weekday(day: &str) -> Option<Weekday> {
+    match day.trim().to_lowercase().get(..3)? {
+        "mon" => Some(Weekday::Mon),
+        "tue" => Some(Weekday::Tue),
+        "wed" => Some(Weekday::Wed),
+        "thu" => Some(Weekday::Thu),
+        "fri" => Some(Weekday::Fri),
+        "sat" => Some(Weekday::Sat),
+        "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn weekday_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "Mondays",
+        Weekday::Tue => "Tuesdays",
+        Weekday::Wed => "Wednesdays",
+        Weekday::Thu => "Thursdays",
+        Weekday::Fri => "Fridays",
+        Weekday::Sat => "Saturdays",
+        Weekday::Sun => "Sundays",
+    }
+}
+
+/// Minimal glob matching: `*` matches any run of characters, `?` exactly one.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    let (mut pi, mut ti) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ti));
+            pi += 1;
+        } else if let Some((star_pi, star_ti)) = star {
+            // Let the last `*` swallow one more character and retry
+            pi = star_pi + 1;
+            ti = star_ti + 1;
+            star = Some((star_pi, star_ti + 1));
+        } else {
+            return false;
+        }
+    }
+
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn rules(yaml: &str) -> Vec<GuardrailConfig> {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn target(branch: &str, inputs: &[(&str, &str)]) -> DispatchTarget {
+        DispatchTarget {
+            repo: "acme/api".to_string(),
+            workflow: "deploy.yml".to_string(),
+            branch: branch.to_string(),
+            inputs: inputs.iter().map(|(n, v)| (n.to_string(), v.to_string())).collect(),
+        }
+    }
+
+    /// Monday 5 January 2026 at `hour:minute` local time.
+    fn monday_at(hour: u32, minute: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 1, 5, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn glob_matching() {
+        assert!(glob_match("main", "main"));
+        assert!(!glob_match("main", "mainline"));
+        assert!(glob_match("release/*", "release/1.2"));
+        assert!(glob_match("release/*", "release/"));
+        assert!(!glob_match("release/*", "releases/1.2"));
+        assert!(glob_match("*-prod-*", "eu-prod-1"));
+        assert!(glob_match("v?.*", "v1.0"));
+        assert!(!glob_match("v?.*", "v10.0"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(!glob_match("a*b*c", "aXbYb"));
+    }
+
+    #[test]
+    fn rules_match_repo_workflow_branch_and_inputs() {
+        let rules = rules(
+            r#"
+- repo: acme/api
+  workflow: deploy.yml
+  branch: "release/*"
+  inputs: [{ name: env, value: prod }]
+"#,
+        );
+        let now = monday_at(12, 0);
+        assert!(check(&rules, &target("release/1.2", &[("dry", "false"), ("env", "prod")]), now).is_some());
+        assert!(check(&rules, &target("main", &[("env", "prod")]), now).is_none());
+        assert!(check(&rules, &target("release/1.2", &[("env", "staging")]), now).is_none());
+        assert!(check(&rules, &target("release/1.2", &[]), now).is_none());
+
+        let mut other_repo = target("release/1.2", &[("env", "prod")]);
+        other_repo.repo = "acme/web".to_string();
+        assert!(check(&rules, &other_repo, now).is_none());
+        let mut other_workflow = target("release/1.2", &[("env", "prod")]);
+        other_workflow.workflow = "ci.yml".to_string();
+        assert!(check(&rules, &other_workflow, now).is_none());
+    }
+
+    #[test]
+    fn phrase_and_banner_come_from_the_matching_rules() {
+        let rules = rules(
+            r#"
+- workflow: deploy.yml
+- branch: main
+  confirm: ship it
+  message: Production deploy
+- branch: "*"
+  message: Watch the dashboards
+- workflow: other.yml
+  confirm: not this one
+  message: Not this one either
+"#,
+        );
+        let guard = check(&rules, &target("main", &[]), monday_at(12, 0)).unwrap();
+        assert_eq!(guard.phrase, "ship it");
+        assert_eq!(guard.banner, "Production deploy | Watch the dashboards");
+        assert_eq!(guard.blocked, None);
+
+        let guard = check(&rules[..1], &target("dev", &[]), monday_at(12, 0)).unwrap();
+        assert_eq!(guard.phrase, "acme/api");
+        assert_eq!(guard.banner, "Protected dispatch: acme/api → deploy.yml @ dev");
+    }
+
+    #[test]
+    fn blocks_dispatches_on_listed_days() {
+        let rules = rules("- blocked_days: [fri, Saturday]\n");
+        let friday = Local.with_ymd_and_hms(2026, 1, 9, 12, 0, 0).unwrap();
+        let saturday = Local.with_ymd_and_hms(2026, 1, 10, 12, 0, 0).unwrap();
+        let blocked = |now| check(&rules, &target("main", &[]), now).unwrap().blocked;
+        assert_eq!(blocked(friday).as_deref(), Some("dispatches are blocked on Fridays"));
+        assert_eq!(blocked(saturday).as_deref(), Some("dispatches are blocked on Saturdays"));
+        assert_eq!(blocked(monday_at(12, 0)), None);
+    }
+
+    #[test]
+    fn blocks_dispatches_outside_allowed_hours() {
+        let rules = rules("- allowed_hours: { from: 9, to: 17 }\n");
+        let blocked = |hour| check(&rules, &target("main", &[]), monday_at(hour, 30)).unwrap().blocked;
+        assert_eq!(blocked(9), None);
+        assert_eq!(blocked(16), None);
+        assert_eq!(
+            blocked(17).as_deref(),
+            Some("dispatches are only allowed between 09:00 and 17:00 (now 17:30)")
+        );
+        assert!(blocked(8).is_some());
+        assert!(blocked(0).is_some());
+    }
+
+    #[test]
+    fn allowed_hours_can_run_past_midnight() {
+        let rules = rules("- allowed_hours: { from: 22, to: 6 }\n");
+        let blocked = |hour| check(&rules, &target("main", &[]), monday_at(hour, 30)).unwrap().blocked;
+        for hour in [22, 23, 0, 5] {
+            assert_eq!(blocked(hour), None, "{}:30 is inside the window", hour);
+        }
+        for hour in [6, 12, 21] {
+            assert!(blocked(hour).is_some(), "{}:30 is outside the window", hour);
+        }
+        assert_eq!(
+            blocked(12).as_deref(),
+            Some("dispatches are only allowed between 22:00 and 06:00 (now 12:30)")
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use std::path::{Path, PathBuf};
+
+/// Name of the history file, kept next to the user config.
+pub const HISTORY_FILE: &str = "history.yml";
+
+/// How many values are remembered for each input.
+const MAX_VALUES_PER_INPUT: usize = 20;
+
+/// Age in days after which a use counts half as much when ranking values.
+const HALF_LIFE_DAYS: f64 = 14.0;
+
+/// A value that was dispatched for one input of a workflow.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct HistoryEntry {
+    pub repo: String,
+    pub workflow: String,
+    pub input: String,
+    pub value: String,
+    pub uses: u32,
+    pub last_used: u64, // unix seconds
+}
+
+/// Values dispatched before, per repo, workflow and input name, so they can
+/// be offered again as suggestions.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default)]
+pub struct InputHistory {
+    #[serde(default)]
+    entries: Vec<HistoryEntry>,
+}
+
+impl InputHistory {
+    /// Load the history file. A missing file is an empty history.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => serde_yaml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    /// Write the history through a temporary file, so a crash never leaves
+    /// a truncated file behind.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let yaml = serde_yaml::to_string(self).map_err(|e| e.to_string())?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        let tmp = PathBuf::from(format!("{}.tmp", path.display()));
+        std::fs::write(&tmp, yaml)
+            .and_then(|()| std::fs::rename(&tmp, path))
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Remember the non-empty values of a dispatch.
+    pub fn record(&mut self, repo: &str, workflow: &str, inputs: &[(String, String)], now: u64) {
+        for (input, value) in inputs.iter().filter(|(_, value)| !value.is_empty()) {
+            let existing = self.entries.iter_mut().find(|e| {
+                e.repo == repo && e.workflow == workflow && e.input == *input && e.value == *value
+            });
+            match existing {
+                Some(entry) => {
+                    entry.uses += 1;
+                    entry.last_used = now;
+                }
+                None => self.entries.push(HistoryEntry {
+                    repo: repo.to_string(),
+                    workflow: workflow.to_string(),
+                    input: input.clone(),
+                    value: value.clone(),
+                    uses: 1,
+                    last_used: now,
+                }),
+            }
+            self.prune(repo, workflow, input, now);
+        }
+    }
+
+    /// Values used for an input before, best first: frequently used values
+    /// rank high, but a value's weight halves every `HALF_LIFE_DAYS`.
+    pub fn values(&self, repo: &str, workflow: &str, input: &str, now: u64) -> Vec<String> {
+        let mut entries: Vec<&HistoryEntry> = self.entries
+            .iter()
+            .filter(|e| e.repo == repo && e.workflow == workflow && e.input == input)
+            .collect();
+        entries.sort_by(|a, b| score(b, now).total_cmp(&score(a, now)));
+        entries.into_iter().map(|e| e.value.clone()).collect()
+    }
+
+    /// Forget the lowest ranked values of an input beyond `MAX_VALUES_PER_INPUT`.
+    fn prune(&mut self, repo: &str, workflow: &str, input: &str, now: u64) {
+        let keep = self.values(repo, workflow, input, now);
+        if keep.len() <= MAX_VALUES_PER_INPUT {
+            return;
+        }
+        let keep = &keep[..MAX_VALUES_PER_INPUT];
+        self.entries.retain(|e| {
+            e.repo != repo || e.workflow != workflow || e.input != input || keep.contains(&e.value)
+        });
+    }
+}
+
+fn score(entry: &HistoryEntry, now: u64) -> f64 {
+    let age_days = now.saturating_sub(entry.last_used) as f64 / 86_400.0;
+    entry.uses as f64 * 0.5f64.powf(age_days / HALF_LIFE_DAYS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 86_400;
+
+    fn inputs(value: &str) -> Vec<(String, String)> {
+        vec![("tag".to_string(), value.to_string())]
+    }
+
+    /// A path in a fresh temporary directory, removed by the caller.
+    fn temp_path(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("dispatchrr-{}-{}", test, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join("nested").join(HISTORY_FILE)
+    }
+
+    #[test]
+    fn ranks_by_uses_decayed_by_age() {
+        let mut history = InputHistory::default();
+        let now = 100 * DAY;
+        for _ in 0..3 {
+            history.record("o/r", "ci.yml", &inputs("old"), now - 42 * DAY);
+        }
+        history.record("o/r", "ci.yml", &inputs("new"), now);
+        history.record("o/r", "ci.yml", &inputs("mid"), now - DAY);
+        history.record("o/r", "ci.yml", &inputs("mid"), now - DAY);
+        history.record("o/r", "other.yml", &inputs("elsewhere"), now);
+        history.record("o/other", "ci.yml", &inputs("elsewhere"), now);
+
+        // 3 uses six weeks ago weigh 3/8, less than one use today
+        assert_eq!(history.values("o/r", "ci.yml", "tag", now), vec!["mid", "new", "old"]);
+        assert_eq!(history.values("o/r", "ci.yml", "other", now), Vec::<String>::new());
+    }
+
+    #[test]
+    fn score_halves_every_half_life() {
+        let entry = HistoryEntry {
+            repo: String::new(),
+            workflow: String::new(),
+            input: String::new(),
+            value: String::new(),
+            uses: 4,
+            last_used: 0,
+        };
+        assert_eq!(score(&entry, 0), 4.0);
+        assert_eq!(score(&entry, 14 * DAY), 2.0);
+        assert_eq!(score(&entry, 28 * DAY), 1.0);
+    }
+
+    #[test]
+    fn keeps_the_best_values() {
+        let mut history = InputHistory::default();
+        for i in 0..=MAX_VALUES_PER_INPUT as u64 {
+            history.record("o/r", "ci.yml", &inputs(&format!("v{}", i)), i * DAY);
+        }
+        let values = history.values("o/r", "ci.yml", "tag", MAX_VALUES_PER_INPUT as u64 * DAY);
+        assert_eq!(values.len(), MAX_VALUES_PER_INPUT);
+        assert!(!values.contains(&"v0".to_string()));
+    }
+
+    #[test]
+    fn skips_empty_values() {
+        let mut history = InputHistory::default();
+        history.record("o/r", "ci.yml", &inputs(""), DAY);
+        assert!(history.entries.is_empty());
+    }
+
+    #[test]
+    fn saves_and_loads() {
+        let path = temp_path("history-roundtrip");
+        assert!(InputHistory::load(&path).unwrap().entries.is_empty());
+
+        let mut history = InputHistory::default();
+        history.record("o/r", "ci.yml", &inputs("v1"), DAY);
+        history.record("o/r", "ci.yml", &inputs("v2"), 2 * DAY);
+        history.record("o/r", "ci.yml", &inputs("v2"), 2 * DAY);
+        history.save(&path).unwrap();
+        assert!(!PathBuf::from(format!("{}.tmp", path.display())).exists());
+
+        let loaded = InputHistory::load(&path).unwrap();
+        assert_eq!(loaded.values("o/r", "ci.yml", "tag", 2 * DAY), vec!["v2", "v1"]);
+        assert_eq!(loaded.entries[1].uses, 2);
+
+        std::fs::write(&path, "entries: [not, an, entry]").unwrap();
+        let error = InputHistory::load(&path).unwrap_err();
+        assert!(error.starts_with(&path.display().to_string()), "{}", error);
+
+        std::fs::remove_dir_all(path.parent().unwrap().parent().unwrap()).unwrap();
+    }
+}
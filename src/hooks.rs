@@ -0,0 +1,319 @@
+use std::io::Write;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::OnceLock;
+use std::thread;
+
+use crate::config::{HookConfig, HookEvent};
+use crate::service::github::GitHubService;
+
+/// Everything a hook can reference through `{placeholders}`.
+#[derive(Debug, Clone)]
+pub struct HookContext {
+    pub event: HookEvent,
+    pub repo: String,
+    pub git_ref: String,
+    pub workflow: String,
+    pub inputs: Vec<(String, String)>,
+    pub run_url: String,    // empty until the run is known
+    pub conclusion: String, // empty for dispatch events
+}
+
+/// Result of one hook, reported back to the event loop.
+#[derive(Debug)]
+pub struct HookOutcome {
+    pub hook: String, // short description, e.g. "POST https://..." or "./announce.sh"
+    pub result: Result<(), String>,
+}
+
+/// Runs hooks on background threads so slow webhooks never block the UI.
+#[derive(Debug)]
+pub struct HookRunner {
+    tx: Sender<HookOutcome>,
+    rx: Receiver<HookOutcome>,
+}
+
+impl Default for HookRunner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HookRunner {
+    pub fn new() -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self { tx, rx }
+    }
+
+    /// Fire every hook that matches the context's repo, workflow and event.
+    pub fn fire(&self, hooks: &[HookConfig], ctx: &HookContext) {
+        for hook in hooks.iter().filter(|h| matches(h, ctx)) {
+            let hook = hook.clone();
+            let ctx = ctx.clone();
+            let tx = self.tx.clone();
+            thread::spawn(move || {
+                let outcome = HookOutcome {
+                    hook: describe(&hook),
+                    result: run_hook(&hook, &ctx),
+                };
+                let _ = tx.send(outcome);
+            });
+        }
+    }
+
+    /// Next finished hook, if any. Never blocks.
+    pub fn try_recv(&self) -> Option<HookOutcome> {
+        self.rx.try_recv().ok()
+    }
+}
+
+/// Descriptions of the hooks that would fire for `ctx`.
+pub fn matching_hooks(hooks: &[HookConfig], ctx: &HookContext) -> Vec<String> {
+    hooks.iter().filter(|h| matches(h, ctx)).map(describe).collect()
+}
+
+fn matches(hook: &HookConfig, ctx: &HookContext) -> bool {
+    hook.on.contains(&ctx.event)
+        && hook.repo.as_ref().is_none_or(|r| *r == ctx.repo)
+        && hook.workflow.as_ref().is_none_or(|w| *w == ctx.workflow)
+}
+
+fn describe(hook: &HookConfig) -> String {
+    match (&hook.url, hook.command.first()) {
+        (Some(url), _) => format!("POST {}", url),
+        (None, Some(program)) => program.clone(),
+        (None, None) => "(empty hook)".to_string(),
+    }
+}
+
+/// The authenticated user, looked up once and shared by all hooks.
+fn actor() -> &'static str {
+    static ACTOR: OnceLock<String> = OnceLock::new();
+    ACTOR.get_or_init(|| GitHubService::new().fetch_viewer_login().unwrap_or_default())
+}
+
+fn event_name(event: HookEvent) -> &'static str {
+    match event {
+        HookEvent::Dispatch => "dispatch",
+        HookEvent::Conclusion => "conclusion",
+    }
+}
+
+/// Placeholder values, in the order they are substituted.
+fn fields(ctx: &HookContext) -> Vec<(&'static str, String)> {
+    let inputs = ctx
+        .inputs
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect::<Vec<_>>()
+        .join(", ");
+    vec![
+        ("event", event_name(ctx.event).to_string()),
+        ("repo", ctx.repo.clone()),
+        ("ref", ctx.git_ref.clone()),
+        ("workflow", ctx.workflow.clone()),
+        ("inputs", inputs),
+        ("actor", actor().to_string()),
+        ("run_url", ctx.run_url.clone()),
+        ("conclusion", ctx.conclusion.clone()),
+    ]
+}
+
+/// Replace `{key}` placeholders with their escaped values. Done in one
+/// pass, so placeholders inside substituted values stay as they are.
+pub fn render_template(template: &str, fields: &[(&str, String)], escape: impl Fn(&str) -> String) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let field = after.find('}').and_then(|end| {
+            let (_, value) = fields.iter().find(|(key, _)| *key == &after[..end])?;
+            Some((end, value))
+        });
+        match field {
+            Some((end, value)) => {
+                rendered.push_str(&escape(value));
+                rest = &after[end + 1..];
+            }
+            None => {
+                rendered.push('{');
+                rest = after;
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Escape a value for use inside a JSON string literal.
+fn json_escape(value: &str) -> String {
+    let quoted = serde_json::Value::String(value.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// Run the hook's command and post to its URL. A failing command doesn't
+/// stop the POST; both errors are reported.
+fn run_hook(hook: &HookConfig, ctx: &HookContext) -> Result<(), String> {
+    let fields = fields(ctx);
+    let mut errors = Vec::new();
+    // The outcome is labelled with the URL, so name the command when both ran
+    let both = hook.url.is_some() && !hook.command.is_empty();
+
+    if let Some((program, args)) = hook.command.split_first()
+        && let Err(e) = run_command(program, args, &fields) {
+        errors.push(if both { format!("{}: {}", program, e) } else { e });
+    }
+
+    if let Some(url) = &hook.url {
+        let body = match &hook.body {
+            Some(template) => render_template(template, &fields, json_escape),
+            None => {
+                let mut payload = serde_json::Map::new();
+                for (key, value) in &fields {
+                    payload.insert(key.to_string(), serde_json::Value::String(value.clone()));
+                }
+                let inputs: serde_json::Map<String, serde_json::Value> = ctx
+                    .inputs
+                    .iter()
+                    .map(|(k, v)| (k.clone(), serde_json::Value::String(v.clone())))
+                    .collect();
+                payload.insert("inputs".to_string(), serde_json::Value::Object(inputs));
+                serde_json::Value::Object(payload).to_string()
+            }
+        };
+        if let Err(e) = post_json(url, &body) {
+            errors.push(if both { format!("POST: {}", e) } else { e });
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+fn run_command(program: &str, args: &[String], fields: &[(&str, String)]) -> Result<(), String> {
+    let args: Vec<String> = args
+        .iter()
+        .map(|a| render_template(a, fields, |v| v.to_string()))
+        .collect();
+    let output = std::process::Command::new(program)
+        .args(&args)
+        .envs(fields.iter().map(|(k, v)| (format!("DISPATCHRR_{}", k.to_uppercase()), v.clone())))
+        .stdin(std::process::Stdio::null())
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("exited with {}: {}", output.status, stderr.trim()));
+    }
+    Ok(())
+}
+
+/// POST a JSON body with `curl`, which ships with every platform we build for.
+fn post_json(url: &str, body: &str) -> Result<(), String> {
+    let mut child = std::process::Command::new("curl")
+        .args([
+            "--silent", "--show-error", "--fail",
+            "--max-time", "15",
+            "-X", "POST",
+            "-H", "Content-Type: application/json",
+            "--data-binary", "@-",
+            url,
+        ])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("failed to run curl: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(body.as_bytes()).map_err(|e| e.to_string())?;
+    }
+
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(stderr.trim().to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hooks(yaml: &str) -> Vec<HookConfig> {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn context(event: HookEvent) -> HookContext {
+        HookContext {
+            event,
+            repo: "acme/api".to_string(),
+            git_ref: "main".to_string(),
+            workflow: "deploy.yml".to_string(),
+            inputs: vec![("env".to_string(), "prod".to_string())],
+            run_url: String::new(),
+            conclusion: String::new(),
+        }
+    }
+
+    #[test]
+    fn renders_placeholders() {
+        let fields = [("repo", "acme/api".to_string()), ("ref", "main".to_string())];
+        assert_eq!(
+            render_template("{repo}@{ref}: {unknown} {ref", &fields, |v| v.to_string()),
+            "acme/api@main: {unknown} {ref"
+        );
+        let fields = [("repo", "say \"hi\"\n".to_string()), ("ref", "main".to_string())];
+        assert_eq!(
+            render_template(r#"{"text":"{repo} on {ref}"}"#, &fields, json_escape),
+            r#"{"text":"say \"hi\"\n on main"}"#
+        );
+    }
+
+    #[test]
+    fn renders_placeholders_in_one_pass() {
+        let fields = [("repo", "o/{ref}".to_string()), ("ref", "main".to_string())];
+        assert_eq!(render_template("{repo}@{ref}", &fields, |v| v.to_string()), "o/{ref}@main");
+    }
+
+    #[test]
+    fn hooks_match_event_repo_and_workflow() {
+        let hooks = hooks(
+            r#"
+- url: https://example.com/all
+- on: [conclusion]
+  command: [./announce.sh, "{conclusion}"]
+- repo: acme/web
+  url: https://example.com/web
+- workflow: deploy.yml
+  on: [dispatch]
+"#,
+        );
+        assert_eq!(
+            matching_hooks(&hooks, &context(HookEvent::Dispatch)),
+            vec!["POST https://example.com/all", "(empty hook)"]
+        );
+        assert_eq!(
+            matching_hooks(&hooks, &context(HookEvent::Conclusion)),
+            vec!["POST https://example.com/all", "./announce.sh"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn commands_get_placeholders_in_arguments_and_environment() {
+        let fields = [("repo", "acme/api".to_string()), ("ref", "main".to_string())];
+        let check = r#"test "$DISPATCHRR_REPO" = acme/api && test "$1" = main"#;
+        let args = ["-c".to_string(), check.to_string(), "sh".to_string(), "{ref}".to_string()];
+        assert_eq!(run_command("sh", &args, &fields), Ok(()));
+
+        let args = ["-c".to_string(), "echo broken >&2; exit 3".to_string()];
+        let error = run_command("sh", &args, &fields).unwrap_err();
+        assert!(error.starts_with("exited with"), "{}", error);
+        assert!(error.ends_with(": broken"), "{}", error);
+    }
+}
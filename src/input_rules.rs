@@ -0,0 +1,232 @@
+use crate::config::InputRuleConfig;
+use crate::domain::{InputCondition, InputField};
+use crate::guardrails::glob_match;
+
+/// Give inputs their group and condition, from `[group: …]` / `[if: …]`
+/// tags in their descriptions and then the first matching config rule, and
+/// list the members of each group together where the first one appears.
+///
+/// Returns problems with description tags, e.g. an unparsable condition.
+pub fn apply(fields: &mut Vec<InputField>, rules: &[InputRuleConfig], repo: &str, workflow: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    for field in fields.iter_mut() {
+        let (description, group, show_if) = take_tags(&field.description);
+        field.description = description;
+        field.group = group;
+        match show_if.as_deref().map(InputCondition::parse) {
+            Some(Ok(condition)) => field.show_if = Some(condition),
+            Some(Err(e)) => problems.push(format!("- {}: [if: …] {}", field.name, e)),
+            None => {}
+        }
+
+        let applies = |rule: &&InputRuleConfig| {
+            rule.repo.as_ref().is_none_or(|r| r == repo)
+                && rule.workflow.as_ref().is_none_or(|w| w == workflow)
+                && glob_match(&rule.input, &field.name)
+        };
+        if let Some(group) = rules.iter().filter(applies).find_map(|rule| rule.group.clone()) {
+            field.group = Some(group);
+        }
+        // Config conditions are already checked when the config loads
+        if let Some(condition) = rules
+            .iter()
+            .filter(applies)
+            .find_map(|rule| InputCondition::parse(rule.show_if.as_deref()?).ok()) {
+            field.show_if = Some(condition);
+        }
+    }
+
+    gather_groups(fields);
+    problems
+}
+
+/// How the inputs popup orders the inputs of each group.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InputSort {
+    #[default]
+    Declared,
+    RequiredFirst,
+    Alphabetical,
+}
+
+impl InputSort {
+    pub fn next(self) -> Self {
+        match self {
+            InputSort::Declared => InputSort::RequiredFirst,
+            InputSort::RequiredFirst => InputSort::Alphabetical,
+            InputSort::Alphabetical => InputSort::Declared,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            InputSort::Declared => "declared",
+            InputSort::RequiredFirst => "required first",
+            InputSort::Alphabetical => "alphabetical",
+        }
+    }
+}
+
+/// Put inputs back in declaration order with their groups gathered, then
+/// order each run of inputs sharing a group by `sort`. Groups stay where
+/// they are declared, so headings don't move when the order changes.
+pub fn sort(fields: &mut Vec<InputField>, sort: InputSort) {
+    fields.sort_by_key(|f| f.position);
+    gather_groups(fields);
+    if sort == InputSort::Declared {
+        return;
+    }
+
+    let mut run = 0;
+    let mut runs = Vec::with_capacity(fields.len());
+    for (idx, field) in fields.iter().enumerate() {
+        if idx > 0 && fields[idx - 1].group != field.group {
+            run += 1;
+        }
+        runs.push(run);
+    }
+    let mut keyed: Vec<(usize, InputField)> = runs.into_iter().zip(fields.drain(..)).collect();
+    match sort {
+        InputSort::Declared => {}
+        InputSort::RequiredFirst => keyed.sort_by_key(|(run, f)| (*run, !f.required)),
+        InputSort::Alphabetical => keyed.sort_by_cached_key(|(run, f)| (*run, f.name.to_lowercase())),
+    }
+    fields.extend(keyed.into_iter().map(|(_, f)| f));
+}
+
+/// Split `[group: …]` and `[if: …]` tags off a description.
+fn take_tags(description: &str) -> (String, Option<String>, Option<String>) {
+    let mut rest = description.to_string();
+    let mut take = |tag: &str| -> Option<String> {
+        let start = rest.find(tag)?;
+        let end = start + rest[start..].find(']')?;
+        let value = rest[start + tag.len()..end].trim().to_string();
+        rest.replace_range(start..=end, "");
+        Some(value)
+    };
+    let group = take("[group:");
+    let show_if = take("[if:");
+    if group.is_none() && show_if.is_none() {
+        return (rest, None, None);
+    }
+    // Close the gaps the tags left, keeping the description's line breaks
+    let description = rest
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string();
+    (description, group.filter(|g| !g.is_empty()), show_if)
+}
+
+/// Move every grouped input up to the first input of its group, keeping
+/// declaration order otherwise.
+fn gather_groups(fields: &mut Vec<InputField>) {
+    let mut ordered: Vec<InputField> = Vec::with_capacity(fields.len());
+    for field in fields.drain(..) {
+        let last_of_group = field.group.as_ref().and_then(|group| {
+            ordered.iter().rposition(|f| f.group.as_ref() == Some(group))
+        });
+        match last_of_group {
+            Some(idx) => ordered.insert(idx + 1, field),
+            None => ordered.push(field),
+        }
+    }
+    *fields = ordered;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::fixtures::input;
+
+    fn described(name: &str, description: &str) -> InputField {
+        let mut field = input(name, "string", "");
+        field.description = description.to_string();
+        field
+    }
+
+    fn names(fields: &[InputField]) -> Vec<&str> {
+        fields.iter().map(|f| f.name.as_str()).collect()
+    }
+
+    #[test]
+    fn takes_tags_off_descriptions() {
+        assert_eq!(
+            take_tags("Traffic share [group: Rollout]  [if: strategy=canary] in percent"),
+            ("Traffic share in percent".to_string(), Some("Rollout".to_string()), Some("strategy=canary".to_string()))
+        );
+        assert_eq!(take_tags("[group:] Note"), ("Note".to_string(), None, None));
+        assert_eq!(take_tags("Unclosed [group: x"), ("Unclosed [group: x".to_string(), None, None));
+    }
+
+    #[test]
+    fn keeps_line_breaks_in_descriptions() {
+        assert_eq!(
+            take_tags("Target [group: Deploy] region\n[if: env=prod]  only"),
+            ("Target region\nonly".to_string(), Some("Deploy".to_string()), Some("env=prod".to_string()))
+        );
+        assert_eq!(
+            take_tags("First line\n  indented  second"),
+            ("First line\n  indented  second".to_string(), None, None)
+        );
+    }
+
+    #[test]
+    fn applies_tags_and_rules_and_gathers_groups() {
+        let rules: Vec<InputRuleConfig> = serde_yaml::from_str(
+            r#"
+- input: "canary_*"
+  group: Rollout
+  show_if: strategy=canary
+- repo: acme/web
+  input: note
+  group: Web
+"#,
+        )
+        .unwrap();
+        let mut fields = vec![
+            described("strategy", "How to roll out [group: Rollout]"),
+            described("note", "Release note"),
+            described("canary_percent", "Traffic share [if: strategy=blue]"),
+            described("dry", "Dry run [if: two words]"),
+        ];
+
+        let problems = apply(&mut fields, &rules, "acme/api", "deploy.yml");
+        assert_eq!(
+            problems,
+            vec!["- dry: [if: …] 'two words' is not `input`, `!input`, `input=value` or `input!=value`"]
+        );
+        assert_eq!(names(&fields), vec!["strategy", "canary_percent", "note", "dry"]);
+        assert_eq!(fields[1].description, "Traffic share");
+        assert_eq!(fields[1].group.as_deref(), Some("Rollout"));
+        assert_eq!(fields[1].show_if, Some(InputCondition::parse("strategy=canary").unwrap()));
+        assert_eq!(fields[2].group, None);
+        assert_eq!(fields[3].show_if, None);
+    }
+
+    #[test]
+    fn sorts_within_groups() {
+        let mut fields: Vec<InputField> = ["omega", "zeta", "delta", "alpha", "Bravo"]
+            .iter()
+            .enumerate()
+            .map(|(position, name)| {
+                let mut field = input(name, "string", "");
+                field.position = position;
+                field
+            })
+            .collect();
+        fields[1].group = Some("Rollout".to_string());
+        fields[3].group = Some("Rollout".to_string());
+        fields[4].required = true;
+
+        sort(&mut fields, InputSort::Alphabetical);
+        assert_eq!(names(&fields), vec!["omega", "alpha", "zeta", "Bravo", "delta"]);
+        sort(&mut fields, InputSort::RequiredFirst);
+        assert_eq!(names(&fields), vec!["omega", "zeta", "alpha", "Bravo", "delta"]);
+        sort(&mut fields, InputSort::Declared);
+        assert_eq!(names(&fields), vec!["omega", "zeta", "alpha", "delta", "Bravo"]);
+    }
+}
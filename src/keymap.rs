@@ -0,0 +1,442 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::config::KeyList;
+
+/// Something a key can be bound to. Names are the `keybindings` config keys.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Quit,
+    Help,
+    NextPanel,
+    PreviousPanel,
+    Down,
+    Up,
+    ScrollLeft,
+    ScrollRight,
+    Select,
+    Search,
+    CommandPalette,
+    AddRepo,
+    ImportRepos,
+    OpenInBrowser,
+    Replays,
+    EditInputs,
+    Failures,
+    RemoveRepo,
+    MoveRepoDown,
+    MoveRepoUp,
+    ToggleFavorite,
+    SetAlias,
+    SetGroup,
+    Dispatch,
+    SaveReplay,
+    EditInEditor,
+    UnsetInput,
+    ResetInputs,
+    SortInputs,
+    DeleteReplay,
+    WatchLogs,
+}
+
+/// Where a key is listened for. Two actions may share a key only when they
+/// never share a scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Main,      // panels, no popup open
+    Inputs,    // inputs popup, not editing a value
+    Replays,   // replays popup
+    LogPrompt, // right after a dispatch
+}
+
+impl Scope {
+    /// Keys the popup handles itself, which no action may take over.
+    fn fixed_keys(self) -> &'static [&'static str] {
+        match self {
+            Scope::Inputs => &["j", "k", "up", "down", "enter", "tab", "shift+tab", "esc"],
+            Scope::Replays => &["j", "k", "up", "down", "enter", "esc"],
+            Scope::Main | Scope::LogPrompt => &[],
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Scope::Main => "panels",
+            Scope::Inputs => "inputs popup",
+            Scope::Replays => "replays popup",
+            Scope::LogPrompt => "log prompt",
+        }
+    }
+}
+
+impl Action {
+    pub const ALL: &[Action] = &[
+        Action::Quit,
+        Action::Help,
+        Action::NextPanel,
+        Action::PreviousPanel,
+        Action::Down,
+        Action::Up,
+        Action::ScrollLeft,
+        Action::ScrollRight,
+        Action::Select,
+        Action::Search,
+        Action::CommandPalette,
+        Action::AddRepo,
+        Action::ImportRepos,
+        Action::OpenInBrowser,
+        Action::Replays,
+        Action::EditInputs,
+        Action::Failures,
+        Action::RemoveRepo,
+        Action::MoveRepoDown,
+        Action::MoveRepoUp,
+        Action::ToggleFavorite,
+        Action::SetAlias,
+        Action::SetGroup,
+        Action::Dispatch,
+        Action::SaveReplay,
+        Action::EditInEditor,
+        Action::UnsetInput,
+        Action::ResetInputs,
+        Action::SortInputs,
+        Action::DeleteReplay,
+        Action::WatchLogs,
+    ];
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q", "esc"],
+            Action::Help => &["?"],
+            Action::NextPanel => &["tab"],
+            Action::PreviousPanel => &["shift+tab"],
+            Action::Down => &["j", "down"],
+            Action::Up => &["k", "up"],
+            Action::ScrollLeft => &["h", "left"],
+            Action::ScrollRight => &["l", "right"],
+            Action::Select => &["enter"],
+            Action::Search => &["/"],
+            Action::CommandPalette => &[":", "ctrl+p"],
+            Action::AddRepo => &["a"],
+            Action::ImportRepos => &["A"],
+            Action::OpenInBrowser => &["v"],
+            Action::Replays => &["r"],
+            Action::EditInputs => &["i"],
+            Action::Failures => &["f"],
+            Action::RemoveRepo => &["d"],
+            Action::MoveRepoDown => &["J"],
+            Action::MoveRepoUp => &["K"],
+            Action::ToggleFavorite => &["p"],
+            Action::SetAlias => &["n"],
+            Action::SetGroup => &["g"],
+            Action::Dispatch => &["D"],
+            Action::SaveReplay => &["S"],
+            Action::EditInEditor => &["E"],
+            Action::UnsetInput => &["x"],
+            Action::ResetInputs => &["X"],
+            Action::SortInputs => &["o"],
+            Action::DeleteReplay => &["d"],
+            Action::WatchLogs => &["l", "L"],
+        }
+    }
+
+    pub fn scopes(self) -> &'static [Scope] {
+        match self {
+            Action::OpenInBrowser | Action::Failures => &[Scope::Main, Scope::LogPrompt],
+            Action::Dispatch
+            | Action::SaveReplay
+            | Action::EditInEditor
+            | Action::UnsetInput
+            | Action::ResetInputs
+            | Action::SortInputs => &[Scope::Inputs],
+            Action::DeleteReplay => &[Scope::Replays],
+            Action::WatchLogs => &[Scope::LogPrompt],
+            _ => &[Scope::Main],
+        }
+    }
+
+    /// Repo management, which only applies while the Repos panel has focus.
+    pub fn repos_panel_only(self) -> bool {
+        matches!(
+            self,
+            Action::RemoveRepo
+                | Action::MoveRepoDown
+                | Action::MoveRepoUp
+                | Action::ToggleFavorite
+                | Action::SetAlias
+                | Action::SetGroup
+        )
+    }
+
+    /// Heading the action is listed under in the help popup.
+    pub fn help_section(self) -> &'static str {
+        match self {
+            _ if self.repos_panel_only() => "Repos Panel",
+            Action::Dispatch
+            | Action::SaveReplay
+            | Action::EditInEditor
+            | Action::UnsetInput
+            | Action::ResetInputs
+            | Action::SortInputs => "Inputs Popup",
+            Action::DeleteReplay => "Replays Popup",
+            Action::WatchLogs => "After Dispatch",
+            _ => "General",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::Help => "Show this help",
+            Action::NextPanel => "Cycle focus between panels",
+            Action::PreviousPanel => "Cycle focus backwards",
+            Action::Down => "Move down / scroll output",
+            Action::Up => "Move up / scroll output",
+            Action::ScrollLeft => "Scroll repo names left",
+            Action::ScrollRight => "Scroll repo names right",
+            Action::Select => "Select / confirm action",
+            Action::Search => "Fuzzy search in focused panel",
+            Action::CommandPalette => "Open the command palette",
+            Action::AddRepo => "Add a new repo",
+            Action::ImportRepos => "Import repos from a user or organization",
+            Action::OpenInBrowser => "Open repo (or dispatched run) in browser",
+            Action::Replays => "Open saved replays",
+            Action::EditInputs => "Edit workflow inputs",
+            Action::Failures => "Browse failures of the last fetched run",
+            Action::RemoveRepo => "Remove repo",
+            Action::MoveRepoDown => "Move repo down",
+            Action::MoveRepoUp => "Move repo up",
+            Action::ToggleFavorite => "Pin / unpin as favorite",
+            Action::SetAlias => "Set alias",
+            Action::SetGroup => "Set group",
+            Action::Dispatch => "Dispatch workflow",
+            Action::SaveReplay => "Save inputs as replay",
+            Action::EditInEditor => "Edit the selected value in $EDITOR",
+            Action::UnsetInput => "Reset the selected input to its default (unset)",
+            Action::ResetInputs => "Reset all inputs to their defaults",
+            Action::SortInputs => "Cycle input order: declared, required first, alphabetical",
+            Action::DeleteReplay => "Delete selected replay",
+            Action::WatchLogs => "Watch / refresh run logs",
+        }
+    }
+
+    /// Name used in the `keybindings` config section.
+    pub fn config_name(self) -> String {
+        serde_yaml::to_string(&self).unwrap_or_default().trim().to_string()
+    }
+}
+
+/// A key with its modifiers, e.g. `ctrl+d`, `shift+tab` or `D`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers, // only CONTROL and ALT; shift is part of the character
+}
+
+impl KeyBinding {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut shift = false;
+        let mut parts: Vec<&str> = text.split('+').collect();
+        // A lone "+" is the plus key, not a separator
+        let key = if text.ends_with("++") || text == "+" {
+            parts.truncate(parts.len().saturating_sub(2));
+            "+"
+        } else {
+            parts.pop().unwrap_or_default()
+        };
+        for modifier in parts {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => modifiers |= KeyModifiers::CONTROL,
+                "alt" | "meta" => modifiers |= KeyModifiers::ALT,
+                "shift" => shift = true,
+                other => return Err(format!("unknown modifier '{}' in '{}'", other, text)),
+            }
+        }
+
+        let code = match key.to_ascii_lowercase().as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" if shift => KeyCode::BackTab,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "space" => KeyCode::Char(' '),
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            f if f.len() > 1 && f.starts_with('f') && f[1..].parse::<u8>().is_ok_and(|n| (1..=12).contains(&n)) => {
+                KeyCode::F(f[1..].parse().unwrap_or(1))
+            }
+            _ => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if shift => KeyCode::Char(c.to_ascii_uppercase()),
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return Err(format!("unknown key '{}'", text)),
+                }
+            }
+        };
+        Ok(Self { code, modifiers })
+    }
+
+    /// Whether a key event is this binding. Terminals report shifted letters
+    /// both with and without the SHIFT modifier, so it is ignored.
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let modifiers = key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        let code = match key.code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => KeyCode::Char(c.to_ascii_lowercase()),
+            code => code,
+        };
+        let own = match self.code {
+            KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::CONTROL) => KeyCode::Char(c.to_ascii_lowercase()),
+            code => code,
+        };
+        own == code && self.modifiers == modifiers
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::BackTab => write!(f, "Shift+Tab"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Delete => write!(f, "Delete"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::PageUp => write!(f, "PageUp"),
+            KeyCode::PageDown => write!(f, "PageDown"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// The active key → action bindings.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<KeyBinding>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .iter()
+            .map(|&action| {
+                let keys = action
+                    .default_keys()
+                    .iter()
+                    .map(|k| KeyBinding::parse(k).expect("default key bindings are valid"))
+                    .collect();
+                (action, keys)
+            })
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// The default keymap with the configured actions rebound. An action
+    /// given an empty list is unbound. Invalid keys, keys bound to two
+    /// actions in the same scope and keys a popup reserves for navigation
+    /// are reported together.
+    pub fn from_config(overrides: &BTreeMap<Action, KeyList>) -> Result<Self, Vec<String>> {
+        let mut keymap = Self::default();
+        let mut problems = Vec::new();
+
+        for (action, keys) in overrides {
+            let parsed: Vec<KeyBinding> = keys
+                .keys()
+                .iter()
+                .filter_map(|k| KeyBinding::parse(k).map_err(|e| problems.push(format!("{}: {}", action.config_name(), e))).ok())
+                .collect();
+            if let Some((_, bound)) = keymap.bindings.iter_mut().find(|(a, _)| a == action) {
+                *bound = parsed;
+            }
+        }
+
+        for (i, (action, keys)) in keymap.bindings.iter().enumerate() {
+            for (other, other_keys) in &keymap.bindings[i + 1..] {
+                if !action.scopes().iter().any(|s| other.scopes().contains(s)) {
+                    continue;
+                }
+                for key in keys.iter().filter(|k| other_keys.contains(k)) {
+                    problems.push(format!(
+                        "'{}' is bound to both {} and {}",
+                        key,
+                        action.config_name(),
+                        other.config_name()
+                    ));
+                }
+            }
+        }
+
+        for (action, keys) in &keymap.bindings {
+            for &scope in action.scopes() {
+                let fixed: Vec<KeyBinding> = scope.fixed_keys().iter().filter_map(|k| KeyBinding::parse(k).ok()).collect();
+                for key in keys.iter().filter(|k| fixed.contains(k)) {
+                    problems.push(format!(
+                        "'{}' is reserved in the {} and can't be bound to {}",
+                        key,
+                        scope.name(),
+                        action.config_name()
+                    ));
+                }
+            }
+        }
+
+        if problems.is_empty() { Ok(keymap) } else { Err(problems) }
+    }
+
+    /// The action a key triggers in `scope`, if any.
+    pub fn action(&self, scope: Scope, key: &KeyEvent) -> Option<Action> {
+        self.bindings
+            .iter()
+            .filter(|(action, _)| action.scopes().contains(&scope))
+            .find(|(_, keys)| keys.iter().any(|k| k.matches(key)))
+            .map(|(action, _)| *action)
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyBinding] {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// The first key bound to `action`, for hints like "press 'l' to ...".
+    pub fn label(&self, action: Action) -> String {
+        self.keys(action).first().map(|k| k.to_string()).unwrap_or_else(|| "(unbound)".to_string())
+    }
+
+    /// All keys bound to `action`, e.g. "j / ↓".
+    pub fn labels(&self, action: Action) -> String {
+        let keys: Vec<String> = self.keys(action).iter().map(|k| k.to_string()).collect();
+        if keys.is_empty() { "(unbound)".to_string() } else { keys.join(" / ") }
+    }
+}
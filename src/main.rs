@@ -1,31 +1,114 @@
 mod app;
+mod cli;
 mod event;
+mod guardrails;
+mod history;
+mod hooks;
+mod input_rules;
+mod keymap;
+mod notify;
+mod sources;
+mod theme;
 mod ui;
+mod validation;
+mod widgets;
 pub mod config;
 pub mod domain;
 pub mod service;
 // mod gh;
 
+use std::path::PathBuf;
+
 use color_eyre::eyre::Result;
-use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
-use ratatui;
+use crossterm::{
+    event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode},
+};
 
-use crate::{app::AppState, event::run};
+use crate::{
+    app::AppState,
+    config::{config_path, migrate_config_file, validate_config_file, CONFIG_VERSION},
+    cli::{parse_args, CliCommand, USAGE},
+    event::run,
+};
 
 fn main() -> Result<()> {
-    let mut state = AppState::new();
+    let cli = match parse_args(std::env::args().skip(1)) {
+        Ok(CliCommand::Run(cli)) => cli,
+        Ok(CliCommand::Help) => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        Ok(CliCommand::ConfigValidate(path)) => std::process::exit(validate_command(path)),
+        Ok(CliCommand::ConfigMigrate(path)) => std::process::exit(migrate_command(path)),
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let mut state = AppState::new(&cli);
 
     color_eyre::install()?;
     let terminal = ratatui::init();
     enable_raw_mode()?;
+    execute!(std::io::stdout(), EnableMouseCapture, EnableBracketedPaste)?;
     let result = run(terminal, &mut state);
+    execute!(std::io::stdout(), DisableBracketedPaste, DisableMouseCapture)?;
     disable_raw_mode()?;
 
     ratatui::restore();
     result
 }
 
+/// `config validate`: exit 0 when the file is clean, 1 otherwise.
+fn validate_command(path: Option<PathBuf>) -> i32 {
+    let path = path.unwrap_or_else(config_path);
+    if !path.exists() {
+        eprintln!("{}: no config file", path.display());
+        return 1;
+    }
+    match validate_config_file(&path) {
+        Ok((_, problems)) if problems.is_empty() => {
+            println!("{}: ok", path.display());
+            0
+        }
+        Ok((_, problems)) => {
+            for problem in &problems {
+                eprintln!("{}: {}", path.display(), problem);
+            }
+            1
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
 
-fn init() {
-
-}
\ No newline at end of file
+/// `config migrate`: upgrade the file in place, keeping a versioned backup.
+fn migrate_command(path: Option<PathBuf>) -> i32 {
+    let path = path.unwrap_or_else(config_path);
+    if !path.exists() {
+        eprintln!("{}: no config file", path.display());
+        return 1;
+    }
+    match migrate_config_file(&path) {
+        Ok(Some(from)) => {
+            println!(
+                "{}: migrated from version {} to {} (backup: {}.v{}.bak)",
+                path.display(), from, CONFIG_VERSION, path.display(), from
+            );
+            0
+        }
+        Ok(None) => {
+            println!("{}: already at version {}", path.display(), CONFIG_VERSION);
+            0
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use std::io::Write;
+
+use crate::config::{DesktopNotification, NotificationsConfig};
+
+/// Notify the user through every channel enabled in `config`.
+///
+/// Escape sequences go straight to stdout; the terminal shows them even while
+/// the TUI owns the screen. The command hook is spawned and not waited on.
+pub fn notify(config: &NotificationsConfig, title: &str, message: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut stdout = std::io::stdout();
+
+    if config.bell {
+        stdout.write_all(b"\x07")?;
+    }
+
+    // Control characters would terminate the OSC sequence early
+    let clean = |s: &str| s.chars().filter(|c| !c.is_control()).collect::<String>();
+    match config.desktop {
+        DesktopNotification::None => {}
+        DesktopNotification::Osc9 => {
+            write!(stdout, "\x1b]9;{}: {}\x07", clean(title), clean(message))?;
+        }
+        DesktopNotification::Osc777 => {
+            write!(stdout, "\x1b]777;notify;{};{}\x07", clean(title), clean(message).replace(';', ","))?;
+        }
+    }
+    stdout.flush()?;
+
+    if let Some((program, args)) = config.command.split_first() {
+        let mut child = std::process::Command::new(program)
+            .args(args)
+            .arg(message)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .map_err(|e| format!("notification command '{}' failed: {}", program, e))?;
+        // Reap the child in the background so it doesn't linger as a zombie
+        std::thread::spawn(move || child.wait());
+    }
+
+    Ok(())
+}
@@ -1,11 +1,248 @@
-use crate::domain::InputField;
+use std::cell::RefCell;
+
+use crate::domain::{InputField, InputState, RepoListing, RunFailure, RunSummary};
 
 #[derive(Debug, Default)]
-pub struct GitHubService;
+pub struct GitHubService {
+    dry_run: bool,
+    recorded: RefCell<Vec<String>>, // mutating commands a dry run would have sent
+}
 
 impl GitHubService {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// A service whose mutating calls are only recorded, never executed.
+    /// Read calls still go to GitHub.
+    pub fn with_dry_run(dry_run: bool) -> Self {
+        Self {
+            dry_run,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Commands recorded instead of executed during a dry run, oldest first.
+    pub fn recorded_calls(&self) -> Vec<String> {
+        self.recorded.borrow().clone()
+    }
+
+    /// Run a `gh` command that changes state on GitHub, or just record it
+    /// when in dry-run mode. `input` is written to the command's stdin, e.g.
+    /// a request body.
+    fn run_mutation(&self, args: &[String], input: Option<&str>, failure: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if self.dry_run {
+            self.recorded.borrow_mut().push(command_preview(args, input));
+            return Ok(());
+        }
+
+        let mut child = std::process::Command::new("gh")
+            .args(args)
+            .stdin(if input.is_some() { std::process::Stdio::piped() } else { std::process::Stdio::null() })
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()?;
+        if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+            use std::io::Write;
+            stdin.write_all(input.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("{}: {}", failure, stderr.trim()).into());
+        }
+        Ok(())
+    }
+
+    /// Login of the user `gh` is authenticated as.
+    pub fn fetch_viewer_login(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let output = std::process::Command::new("gh")
+            .args(["api", "user", "--jq", ".login"])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("gh cli error: {}", stderr.trim()).into());
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// The authenticated user's login followed by the organizations they
+    /// belong to, for owner autocomplete.
+    pub fn fetch_owner_logins(&self) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let query = "query { viewer { login organizations(first: 100) { nodes { login } } } }";
+        let output = std::process::Command::new("gh")
+            .args(["api", "graphql", "-f", &format!("query={}", query)])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("gh cli error: {}", stderr.trim()).into());
+        }
+
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        let viewer = &json["data"]["viewer"];
+        let mut logins: Vec<String> = viewer["login"].as_str().map(String::from).into_iter().collect();
+        logins.extend(
+            viewer["organizations"]["nodes"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|n| n["login"].as_str().map(String::from)),
+        );
+        Ok(logins)
+    }
+
+    /// Names (without the owner) of a user's or organization's
+    /// non-archived repos, for repo name autocomplete.
+    pub fn list_repo_names(&self, owner: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let output = std::process::Command::new("gh")
+            .args(["repo", "list", owner, "--no-archived", "--limit", "1000", "--json", "name", "--jq", ".[].name"])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("gh cli error: {}", stderr.trim()).into());
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect())
+    }
+
+    /// Most recent tag names of a repo, newest first, for input suggestions.
+    pub fn list_recent_tags(&self, repo_name: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        self.api_lines(&format!("repos/{}/tags?per_page=15", repo_name), ".[].name")
+    }
+
+    /// SHAs of the most recent commits on a branch, newest first, for input
+    /// suggestions.
+    pub fn list_recent_commits(&self, repo_name: &str, branch: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        self.api_lines(&format!("repos/{}/commits?sha={}&per_page=15", repo_name, branch), ".[].sha")
+    }
+
+    /// Tag names of a repo's releases, newest first.
+    pub fn list_release_tags(&self, repo_name: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        self.api_lines(&format!("repos/{}/releases?per_page=30", repo_name), ".[].tag_name")
+    }
+
+    /// Names of a repo's deployment environments.
+    pub fn list_environments(&self, repo_name: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        self.api_lines(&format!("repos/{}/environments", repo_name), ".environments[].name")
+    }
+
+    /// Lines printed by `gh api <path> --jq <jq>`.
+    fn api_lines(&self, path: &str, jq: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let output = std::process::Command::new("gh")
+            .args(["api", path, "--jq", jq])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("gh cli error: {}", stderr.trim()).into());
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect())
+    }
+
+    /// List the non-archived repos of a user or organization, or of the
+    /// authenticated user when `owner` is `None`, via `gh api graphql`.
+    /// Each listing counts the default branch's dispatchable workflows.
+    pub fn list_owner_repos(&self, owner: Option<&str>) -> Result<Vec<RepoListing>, Box<dyn std::error::Error>> {
+        const MAX_PAGES: usize = 20; // 1000 repos
+
+        let connection = "repositories(first: 50, after: $cursor, orderBy: {field: NAME, direction: ASC}) {
+                    pageInfo { hasNextPage endCursor }
+                    nodes {
+                        nameWithOwner
+                        description
+                        isArchived
+                        object(expression: \"HEAD:.github/workflows/\") {
+                            ... on Tree {
+                                entries {
+                                    object { ... on Blob { text } }
+                                }
+                            }
+                        }
+                    }
+                }";
+        let query = match owner {
+            Some(_) => format!(
+                "query($owner: String!, $cursor: String) {{ repositoryOwner(login: $owner) {{ {} }} }}",
+                connection
+            ),
+            None => format!(
+                "query($cursor: String) {{ viewer {{ {} }} }}",
+                connection.replacen("first: 50,", "first: 50, affiliations: [OWNER, ORGANIZATION_MEMBER, COLLABORATOR],", 1)
+            ),
+        };
+
+        let mut listings = Vec::new();
+        let mut cursor: Option<String> = None;
+        for _ in 0..MAX_PAGES {
+            let mut args = vec!["api".to_string(), "graphql".to_string(), "-f".to_string(), format!("query={}", query)];
+            if let Some(owner) = owner {
+                args.extend(["-F".to_string(), format!("owner={}", owner)]);
+            }
+            if let Some(cursor) = &cursor {
+                args.extend(["-f".to_string(), format!("cursor={}", cursor)]);
+            }
+
+            let output = std::process::Command::new("gh").args(&args).output()?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(format!("gh cli error: {}", stderr.trim()).into());
+            }
+
+            let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+            let owner_node = match owner {
+                Some(_) => &json["data"]["repositoryOwner"],
+                None => &json["data"]["viewer"],
+            };
+            if owner_node.is_null() {
+                return Err(format!("No user or organization named '{}'", owner.unwrap_or_default()).into());
+            }
+
+            let repositories = &owner_node["repositories"];
+            for node in repositories["nodes"].as_array().into_iter().flatten() {
+                if node["isArchived"].as_bool().unwrap_or(false) {
+                    continue;
+                }
+                let dispatchable = node["object"]["entries"]
+                    .as_array()
+                    .map(|entries| {
+                        entries
+                            .iter()
+                            .filter(|e| e["object"]["text"].as_str().is_some_and(|t| t.contains("workflow_dispatch")))
+                            .count()
+                    })
+                    .unwrap_or(0);
+                listings.push(RepoListing {
+                    name: node["nameWithOwner"].as_str().unwrap_or_default().to_string(),
+                    description: node["description"].as_str().unwrap_or_default().to_string(),
+                    dispatchable,
+                });
+            }
+
+            let page_info = &repositories["pageInfo"];
+            if !page_info["hasNextPage"].as_bool().unwrap_or(false) {
+                break;
+            }
+            cursor = page_info["endCursor"].as_str().map(String::from);
+        }
+        Ok(listings)
     }
 
     /// Fetch a repo's branches and workflow file names via `gh api graphql`
@@ -155,8 +392,7 @@ impl GitHubService {
 
         // Decode base64 content (gh returns it with newlines)
         let b64_content = String::from_utf8_lossy(&output.stdout)
-            .replace('\n', "")
-            .replace('\r', "");
+            .replace(['\n', '\r'], "");
 
         use base64::Engine;
         let yaml_bytes = base64::engine::general_purpose::STANDARD
@@ -171,10 +407,11 @@ impl GitHubService {
         let mut inputs_list: Vec<String> = Vec::new();
         let mut fields: Vec<InputField> = Vec::new();
 
-        // Handle `on.workflow_dispatch.inputs`
+        // Handle `on.workflow_dispatch.inputs`. `Mapping` keeps the keys in
+        // the order they appear in the file, and so do the fields.
         let dispatch = &yaml_value["on"]["workflow_dispatch"];
         if let Some(inputs_map) = dispatch["inputs"].as_mapping() {
-            for (key, val) in inputs_map {
+            for (position, (key, val)) in inputs_map.iter().enumerate() {
                 let name = key.as_str().unwrap_or("unknown").to_string();
                 let desc = val["description"].as_str().unwrap_or("").to_string();
                 let required = val["required"].as_bool().unwrap_or(false);
@@ -217,6 +454,11 @@ impl GitHubService {
                     value: default_value.clone(),
                     default_value,
                     options,
+                    state: InputState::Unset,
+                    source: None,
+                    group: None,
+                    show_if: None,
+                    position,
                 });
             }
         }
@@ -225,34 +467,17 @@ impl GitHubService {
     }
 
     pub fn dispatch_workflow(&self, repo_name: &str, branch: &str, workflow_filename: &str, inputs: &[InputField]) -> Result<(Vec<String>, String), Box<dyn std::error::Error>> {
-         let mut args = vec![
-            "workflow".to_string(),
-            "run".to_string(),
-            workflow_filename.to_string(),
-            "--repo".to_string(),
-            repo_name.to_string(),
-            "--ref".to_string(),
-            branch.to_string(),
-        ];
-
-        for field in inputs {
-            if !field.value.is_empty() {
-                args.push("-f".to_string());
-                args.push(format!("{}={}", field.name, field.value));
-            }
-        }
-
-        let preview = format!("gh {}", args.join(" "));
-
-        let output = std::process::Command::new("gh")
-            .args(&args)
-            .output()?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("Workflow dispatch failed: {}", stderr.trim()).into());
-        }
+        self.dispatch_with_inputs(repo_name, branch, workflow_filename, &dispatch_inputs(inputs), inputs)
+    }
 
+    /// Dispatch a workflow with explicit `name=value` inputs (e.g. from a replay).
+    /// `fields` are the workflow's declared inputs, used to type the values.
+    pub fn dispatch_with_inputs(&self, repo_name: &str, branch: &str, workflow_filename: &str, inputs: &[(String, String)], fields: &[InputField]) -> Result<(Vec<String>, String), Box<dyn std::error::Error>> {
+        let args = dispatch_args(repo_name, workflow_filename);
+        let body = dispatch_body(branch, inputs, fields);
+        let preview = command_preview(&args, Some(&body));
+        self.run_mutation(&args, Some(&body), "Workflow dispatch failed")
+            .map_err(|e| explain_dispatch_error(e.to_string()))?;
         Ok((args, preview))
     }
 
@@ -291,12 +516,10 @@ impl GitHubService {
             ])
             .output()?;
 
+        // The full log is returned so failures can be located in it; the
+        // caller decides how much of it to show.
         let logs = if log_output.status.success() {
-            let full_log = String::from_utf8_lossy(&log_output.stdout).to_string();
-            // Truncate to last 200 lines to fit in the output panel
-            let lines: Vec<&str> = full_log.lines().collect();
-            let start = if lines.len() > 200 { lines.len() - 200 } else { 0 };
-            lines[start..].join("\n")
+            String::from_utf8_lossy(&log_output.stdout).to_string()
         } else {
             let stderr = String::from_utf8_lossy(&log_output.stderr);
             format!("(logs not yet available: {})", stderr.trim())
@@ -305,4 +528,391 @@ impl GitHubService {
         Ok((run_id, status, conclusion, logs))
     }
 
+    /// List the most recent `workflow_dispatch` runs of a workflow on a branch,
+    /// newest first, optionally only those `actor` triggered.
+    pub fn list_dispatched_runs(&self, repo_name: &str, workflow_filename: &str, branch: &str, actor: Option<&str>) -> Result<Vec<RunSummary>, Box<dyn std::error::Error>> {
+        let mut command = std::process::Command::new("gh");
+        command.args([
+            "run", "list",
+            "--repo", repo_name,
+            "--workflow", workflow_filename,
+            "--branch", branch,
+            "--event", "workflow_dispatch",
+            "--limit", "10",
+            "--json", "databaseId,status,conclusion,createdAt,url",
+        ]);
+        if let Some(actor) = actor {
+            command.args(["--user", actor]);
+        }
+        let output = command.output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Failed to list runs: {}", stderr.trim()).into());
+        }
+
+        let runs: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        let summaries = runs
+            .as_array()
+            .map(|runs| {
+                runs.iter()
+                    .filter_map(|r| {
+                        Some(RunSummary {
+                            id: r["databaseId"].as_u64()?,
+                            status: r["status"].as_str().unwrap_or("unknown").to_string(),
+                            conclusion: r["conclusion"].as_str().unwrap_or("").to_string(),
+                            created_at: r["createdAt"].as_str().unwrap_or("").to_string(),
+                            url: r["url"].as_str().unwrap_or("").to_string(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(summaries)
+    }
+
+    /// Fetch failure annotations for every failed job of a run.
+    ///
+    /// Jobs come from `gh api repos/{repo}/actions/runs/{id}/jobs`; each failed
+    /// job is a check run whose annotations hold the messages GitHub shows in
+    /// the run summary. The failing step is taken from the job's step list.
+    pub fn fetch_run_failures(&self, repo_name: &str, run_id: u64) -> Result<Vec<RunFailure>, Box<dyn std::error::Error>> {
+        // A page holds at most 100 jobs, so large matrices need `--paginate`;
+        // `--jq` prints each job on its own line across all pages
+        let jobs_output = std::process::Command::new("gh")
+            .args([
+                "api",
+                &format!("repos/{}/actions/runs/{}/jobs?per_page=100", repo_name, run_id),
+                "--paginate",
+                "--jq",
+                ".jobs[]",
+            ])
+            .output()?;
+
+        if !jobs_output.status.success() {
+            let stderr = String::from_utf8_lossy(&jobs_output.stderr);
+            return Err(format!("Failed to list run jobs: {}", stderr.trim()).into());
+        }
+
+        let jobs = String::from_utf8_lossy(&jobs_output.stdout)
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<serde_json::Value>, _>>()?;
+        let mut failures: Vec<RunFailure> = Vec::new();
+
+        for job in &jobs {
+            if job["conclusion"].as_str() != Some("failure") {
+                continue;
+            }
+            let job_name = job["name"].as_str().unwrap_or("unknown job").to_string();
+            let failed_step = job["steps"]
+                .as_array()
+                .and_then(|steps| {
+                    steps
+                        .iter()
+                        .find(|s| s["conclusion"].as_str() == Some("failure"))
+                })
+                .and_then(|s| s["name"].as_str().map(String::from));
+
+            let Some(job_id) = job["id"].as_u64() else {
+                continue;
+            };
+            let annotations_output = std::process::Command::new("gh")
+                .args([
+                    "api",
+                    &format!("repos/{}/check-runs/{}/annotations", repo_name, job_id),
+                ])
+                .output()?;
+
+            let annotations: serde_json::Value = if annotations_output.status.success() {
+                serde_json::from_slice(&annotations_output.stdout).unwrap_or_default()
+            } else {
+                serde_json::Value::Null
+            };
+
+            let before = failures.len();
+            for annotation in annotations.as_array().into_iter().flatten() {
+                if annotation["annotation_level"].as_str() != Some("failure") {
+                    continue;
+                }
+                let message = annotation["message"].as_str().unwrap_or("").trim().to_string();
+                if message.is_empty() {
+                    continue;
+                }
+                failures.push(RunFailure {
+                    job: job_name.clone(),
+                    step: failed_step.clone(),
+                    message,
+                    log_line: None,
+                });
+            }
+
+            // A failed job without annotations still deserves an entry
+            if failures.len() == before {
+                failures.push(RunFailure {
+                    job: job_name,
+                    message: match &failed_step {
+                        Some(step) => format!("Step '{}' failed", step),
+                        None => "Job failed".to_string(),
+                    },
+                    step: failed_step,
+                    log_line: None,
+                });
+            }
+        }
+
+        Ok(failures)
+    }
+
+}
+
+/// Split a pasted repo reference into owner and name. Accepts `owner/repo`,
+/// `https://github.com/owner/repo` (with or without scheme, `.git` suffix
+/// or trailing path such as `/tree/main`) and `git@github.com:owner/repo.git`.
+pub fn parse_repo_reference(text: &str) -> Option<(String, String)> {
+    let text = text.trim();
+    let path = text
+        .strip_prefix("git@github.com:")
+        .or_else(|| {
+            let without_scheme = text
+                .strip_prefix("https://")
+                .or_else(|| text.strip_prefix("http://"))
+                .unwrap_or(text);
+            without_scheme
+                .strip_prefix("www.github.com/")
+                .or_else(|| without_scheme.strip_prefix("github.com/"))
+        })
+        .unwrap_or(text);
+
+    let mut parts = path.split('/');
+    let owner = parts.next()?.trim();
+    let name = parts.next()?.trim();
+    let name = name.strip_suffix(".git").unwrap_or(name);
+    if owner.is_empty() || name.is_empty() || owner.contains(':') {
+        return None;
+    }
+    Some((owner.to_string(), name.to_string()))
+}
+
+/// Why `owner` or `name` can't be a GitHub repo, if they can't.
+pub fn validate_repo_name(owner: &str, name: &str) -> Option<String> {
+    if owner.is_empty() || name.is_empty() {
+        return Some("Both owner and repo are required.".to_string());
+    }
+    if !owner.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') || owner.starts_with('-') {
+        return Some(format!("'{}' is not a valid owner: use letters, digits and '-'", owner));
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')) || name == "." || name == ".." {
+        return Some(format!("'{}' is not a valid repo name: use letters, digits, '-', '_' and '.'", name));
+    }
+    None
+}
+
+/// The `gh api` call that dispatches a workflow. The request body, from
+/// `dispatch_body`, is read from stdin.
+pub fn dispatch_args(repo_name: &str, workflow_filename: &str) -> Vec<String> {
+    vec![
+        "api".to_string(),
+        "--method".to_string(),
+        "POST".to_string(),
+        format!("repos/{}/actions/workflows/{}/dispatches", repo_name, workflow_filename),
+        "--input".to_string(),
+        "-".to_string(),
+    ]
+}
+
+/// The inputs a dispatch sends: every set input, including empty ones,
+/// unless its condition hides it.
+pub fn dispatch_inputs(fields: &[InputField]) -> Vec<(String, String)> {
+    fields
+        .iter()
+        .filter(|f| f.is_set() && f.is_shown(fields))
+        .map(|f| (f.name.clone(), f.value.clone()))
+        .collect()
+}
+
+/// JSON body of a workflow dispatch. Inputs `fields` declares as boolean
+/// or number are sent as JSON booleans and numbers, the rest as strings.
+pub fn dispatch_body(branch: &str, inputs: &[(String, String)], fields: &[InputField]) -> String {
+    let typed: serde_json::Map<String, serde_json::Value> = inputs
+        .iter()
+        .map(|(name, value)| {
+            let input_type = fields.iter().find(|f| f.name == *name).map(|f| f.input_type.as_str());
+            let json = match input_type {
+                Some("boolean") if value == "true" || value == "false" => serde_json::Value::Bool(value == "true"),
+                Some("number") => value
+                    .trim()
+                    .parse::<serde_json::Number>()
+                    .map(serde_json::Value::Number)
+                    .unwrap_or_else(|_| serde_json::Value::String(value.clone())),
+                _ => serde_json::Value::String(value.clone()),
+            };
+            (name.clone(), json)
+        })
+        .collect();
+    serde_json::json!({ "ref": branch, "inputs": typed }).to_string()
+}
+
+/// A `gh` command as a user would type it, with any stdin body as a here-string.
+pub fn command_preview(args: &[String], input: Option<&str>) -> String {
+    match input {
+        Some(body) => format!("gh {} <<< '{}'", args.join(" "), body.replace('\'', "'\\''")),
+        None => format!("gh {}", args.join(" ")),
+    }
+}
+
+/// Add a hint to GitHub's replies about inputs it rejected, which `gh`
+/// passes on as raw API errors.
+fn explain_dispatch_error(message: String) -> String {
+    let hint = if message.contains("Unexpected inputs provided") {
+        "the workflow on this ref doesn't declare some of these inputs"
+    } else if message.contains("Required input") {
+        "a required input was left empty"
+    } else if message.contains("not in the list of allowed values") {
+        "a choice input has a value the workflow doesn't offer"
+    } else if message.contains("too large") || message.contains("too many") || message.contains("may only define up to") {
+        "the inputs exceed GitHub's limits; lower `limits` in the config to catch this before dispatching"
+    } else {
+        return message;
+    };
+    format!("{}\n\nHint: {}.", message, hint)
+}
+
+/// Scan a `gh run view --log` dump for `##[error]` and `::error` markers.
+///
+/// Each log line is `job<TAB>step<TAB>timestamp message`, so the job and step
+/// come straight from the first two columns.
+pub fn extract_log_errors(log_lines: &[String]) -> Vec<RunFailure> {
+    let mut failures = Vec::new();
+
+    for (idx, line) in log_lines.iter().enumerate() {
+        let mut columns = line.splitn(3, '\t');
+        let (job, step, rest) = match (columns.next(), columns.next(), columns.next()) {
+            (Some(job), Some(step), Some(rest)) => (job, step, rest),
+            _ => ("", "", line.as_str()),
+        };
+
+        // Markers only count at the start of the message, after the
+        // timestamp, so text like `foo::errors::Bar` isn't a failure
+        let text = match rest.trim_start_matches('\u{feff}').split_once(' ') {
+            Some((stamp, text)) if is_log_timestamp(stamp) => text,
+            _ => rest,
+        };
+        let message = if let Some(message) = text.strip_prefix("##[error]") {
+            message.trim()
+        } else if let Some(after) = text.strip_prefix("::error")
+            && (after.starts_with(' ') || after.starts_with("::"))
+        {
+            // `::error file=app.js,line=1::Something broke` — message follows the second `::`
+            after.split_once("::").map(|(_, msg)| msg).unwrap_or(after).trim()
+        } else {
+            continue;
+        };
+
+        if message.is_empty() {
+            continue;
+        }
+
+        failures.push(RunFailure {
+            job: job.to_string(),
+            step: if step.is_empty() { None } else { Some(step.to_string()) },
+            message: message.to_string(),
+            log_line: Some(idx),
+        });
+    }
+
+    failures
+}
+
+/// Whether `text` is the timestamp GitHub puts before each log message,
+/// like `2026-01-08T20:35:32.1234567Z`.
+fn is_log_timestamp(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes.len() >= 20 && bytes[..4].iter().all(u8::is_ascii_digit) && bytes[10] == b'T' && text.ends_with('Z')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::fixtures::input as field;
+    use crate::domain::InputCondition;
+
+    fn log(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn extracts_error_markers_after_the_timestamp() {
+        let lines = log(&[
+            "build\tCompile\t2026-01-08T20:35:32.1234567Z ##[error]Process completed with exit code 1.",
+            "build\tCompile\t2026-01-08T20:35:31.0000000Z ::error file=app.js,line=1::Something broke",
+            "test\tRun\t\u{feff}2026-01-08T20:35:33.0000000Z ::error::Bare message",
+            "test\t\t2026-01-08T20:35:34.0000000Z ##[error]  ",
+        ]);
+        let found: Vec<_> = extract_log_errors(&lines)
+            .into_iter()
+            .map(|f| (f.job, f.step, f.message, f.log_line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("build".to_string(), Some("Compile".to_string()), "Process completed with exit code 1.".to_string(), Some(0)),
+                ("build".to_string(), Some("Compile".to_string()), "Something broke".to_string(), Some(1)),
+                ("test".to_string(), Some("Run".to_string()), "Bare message".to_string(), Some(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_markers_inside_log_text() {
+        let lines = log(&[
+            "build\tCompile\t2026-01-08T20:35:32.1234567Z error: cannot find foo::errors::Bar",
+            "build\tCompile\t2026-01-08T20:35:32.1234567Z echo \"##[error]not a command\"",
+            "build\tCompile\t2026-01-08T20:35:32.1234567Z ::errors are fine",
+        ]);
+        assert!(extract_log_errors(&lines).is_empty());
+    }
+
+    #[test]
+    fn dispatch_body_sends_set_inputs_typed() {
+        let mut fields = vec![
+            field("deploy", "boolean", "false"),
+            field("replicas", "number", "1"),
+            field("note", "string", "hello"),
+            field("region", "string", "eu"),
+            field("flag", "boolean", "false"),
+        ];
+        fields[0].set_value("true");
+        fields[1].set_value(" 3 ");
+        fields[2].set_value("");
+        fields[4].set_value("yes");
+
+        let inputs = dispatch_inputs(&fields);
+        assert_eq!(
+            dispatch_body("main", &inputs, &fields),
+            r#"{"ref":"main","inputs":{"deploy":true,"replicas":3,"note":"","flag":"yes"}}"#
+        );
+    }
+
+    #[test]
+    fn dispatch_inputs_leave_out_hidden_inputs() {
+        let mut fields = vec![field("deploy", "boolean", "false"), field("region", "string", "")];
+        fields[1].show_if = Some(InputCondition::parse("deploy").unwrap());
+        fields[1].set_value("eu");
+        assert_eq!(dispatch_inputs(&fields), Vec::<(String, String)>::new());
+
+        fields[0].set_value("true");
+        assert_eq!(
+            dispatch_inputs(&fields),
+            vec![("deploy".to_string(), "true".to_string()), ("region".to_string(), "eu".to_string())]
+        );
+    }
+
+    #[test]
+    fn dispatch_body_sends_undeclared_inputs_as_strings() {
+        let inputs = vec![("count".to_string(), "2".to_string())];
+        assert_eq!(dispatch_body("v1", &inputs, &[]), r#"{"ref":"v1","inputs":{"count":"2"}}"#);
+    }
 }
@@ -0,0 +1,76 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use crate::service::github::GitHubService;
+
+/// Suggestion values fetched from GitHub. Suggestions are a convenience, so
+/// a failed lookup comes back empty rather than as an error.
+#[derive(Debug)]
+pub enum Lookup {
+    /// You and the organizations you belong to, for the Add Repo owner field.
+    OwnerLogins(Vec<String>),
+    /// An owner's repos, for the Add Repo name field.
+    RepoNames { owner: String, names: Vec<String> },
+    /// Recent tags or commits for an input, see `dynamic_values_key`.
+    DynamicValues { key: String, values: Vec<String> },
+}
+
+/// Runs suggestion lookups on background threads and hands the results back
+/// to the event loop through a channel, so popups never wait on `gh`.
+#[derive(Debug)]
+pub struct Lookups {
+    tx: Sender<Lookup>,
+    rx: Receiver<Lookup>,
+}
+
+impl Default for Lookups {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Lookups {
+    pub fn new() -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self { tx, rx }
+    }
+
+    pub fn owner_logins(&self) {
+        self.spawn(|github| Lookup::OwnerLogins(github.fetch_owner_logins().unwrap_or_default()));
+    }
+
+    pub fn repo_names(&self, owner: String) {
+        self.spawn(move |github| {
+            let names = github.list_repo_names(&owner).unwrap_or_default();
+            Lookup::RepoNames { owner, names }
+        });
+    }
+
+    /// The repo's latest tags, delivered under `key`.
+    pub fn recent_tags(&self, key: String, repo: String) {
+        self.spawn(move |github| {
+            let values = github.list_recent_tags(&repo).unwrap_or_default();
+            Lookup::DynamicValues { key, values }
+        });
+    }
+
+    /// The latest commit SHAs of a branch, delivered under `key`.
+    pub fn recent_commits(&self, key: String, repo: String, branch: String) {
+        self.spawn(move |github| {
+            let values = github.list_recent_commits(&repo, &branch).unwrap_or_default();
+            Lookup::DynamicValues { key, values }
+        });
+    }
+
+    fn spawn(&self, lookup: impl FnOnce(&GitHubService) -> Lookup + Send + 'static) {
+        let tx = self.tx.clone();
+        thread::spawn(move || {
+            let _ = tx.send(lookup(&GitHubService::new()));
+        });
+    }
+
+    /// Next finished lookup, if any. Never blocks.
+    pub fn try_recv(&self) -> Option<Lookup> {
+        self.rx.try_recv().ok()
+    }
+}
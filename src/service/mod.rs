@@ -1,3 +1,5 @@
 pub mod github;
+pub mod lookups;
+pub mod run_tracker;
 
 pub use github::GitHubService;
@@ -0,0 +1,160 @@
+use std::collections::HashSet;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use chrono::DateTime;
+
+use crate::domain::RunSummary;
+use crate::service::github::GitHubService;
+
+/// Delay before the first poll; GitHub takes a moment to create the run.
+const FIRST_POLL_DELAY: Duration = Duration::from_secs(3);
+/// How often a tracked run is polled afterwards.
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+/// Give up on runs that haven't concluded after this long.
+const MAX_TRACKING_TIME: Duration = Duration::from_secs(6 * 60 * 60);
+/// Runs created slightly before the dispatch still count as ours (clock skew).
+const CLOCK_SKEW_SECS: u64 = 30;
+/// Consecutive `gh` failures tolerated before tracking is abandoned.
+const MAX_POLL_ERRORS: u32 = 3;
+
+/// A dispatch whose run we want to follow until it concludes.
+#[derive(Debug, Clone)]
+pub struct TrackedRun {
+    pub repo: String,
+    pub workflow: String,
+    pub branch: String,
+    pub inputs: Vec<(String, String)>, // inputs sent with the dispatch, for hooks
+    pub dispatched_at: u64,            // unix seconds
+}
+
+#[derive(Debug)]
+pub enum TrackerEvent {
+    /// The run created by the dispatch showed up on GitHub.
+    Started { run: TrackedRun, run_id: u64, url: String },
+    /// The run finished with the given conclusion.
+    Concluded { run: TrackedRun, run_id: u64, conclusion: String, url: String },
+    /// Tracking stopped without a conclusion.
+    Failed { run: TrackedRun, error: String },
+}
+
+/// Polls dispatched runs on background threads and hands results back to the
+/// event loop through a channel, so the UI never blocks on `gh`.
+#[derive(Debug)]
+pub struct RunTracker {
+    tx: Sender<TrackerEvent>,
+    rx: Receiver<TrackerEvent>,
+    claimed: Arc<Mutex<HashSet<u64>>>, // run ids already matched to a tracked dispatch
+}
+
+impl Default for RunTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RunTracker {
+    pub fn new() -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self { tx, rx, claimed: Arc::default() }
+    }
+
+    /// Start following a dispatched run.
+    pub fn track(&self, run: TrackedRun) {
+        let tx = self.tx.clone();
+        let claimed = Arc::clone(&self.claimed);
+        thread::spawn(move || poll_run(run, tx, claimed));
+    }
+
+    /// Next pending event, if any. Never blocks.
+    pub fn try_recv(&self) -> Option<TrackerEvent> {
+        self.rx.try_recv().ok()
+    }
+}
+
+fn poll_run(run: TrackedRun, tx: Sender<TrackerEvent>, claimed: Arc<Mutex<HashSet<u64>>>) {
+    let github = GitHubService::new();
+    let started = Instant::now();
+    let mut announced = false;
+    let mut errors = 0;
+    let mut run_id = None;
+    // Only runs we triggered ourselves; without a login, any dispatched run
+    let actor = github.fetch_viewer_login().ok().filter(|login| !login.is_empty());
+
+    thread::sleep(FIRST_POLL_DELAY);
+    while started.elapsed() < MAX_TRACKING_TIME {
+        match github.list_dispatched_runs(&run.repo, &run.workflow, &run.branch, actor.as_deref()) {
+            Ok(runs) => {
+                errors = 0;
+                let ours = match run_id {
+                    Some(id) => runs.into_iter().find(|r| r.id == id),
+                    None => claim_run(runs, run.dispatched_at, &claimed),
+                };
+                if let Some(summary) = ours {
+                    run_id = Some(summary.id);
+                    if !announced {
+                        announced = true;
+                        let event = TrackerEvent::Started {
+                            run: run.clone(),
+                            run_id: summary.id,
+                            url: summary.url.clone(),
+                        };
+                        if tx.send(event).is_err() {
+                            return;
+                        }
+                    }
+                    if summary.status == "completed" {
+                        let _ = tx.send(TrackerEvent::Concluded {
+                            run,
+                            run_id: summary.id,
+                            conclusion: summary.conclusion,
+                            url: summary.url,
+                        });
+                        return;
+                    }
+                }
+            }
+            Err(e) => {
+                errors += 1;
+                if errors >= MAX_POLL_ERRORS {
+                    let _ = tx.send(TrackerEvent::Failed { run, error: e.to_string() });
+                    return;
+                }
+            }
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+
+    let _ = tx.send(TrackerEvent::Failed {
+        run,
+        error: "run did not conclude within 6 hours".to_string(),
+    });
+}
+
+/// Current time in unix seconds.
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Take the oldest run created after the dispatch that no other tracked
+/// dispatch has claimed, so back-to-back dispatches each get their own run.
+fn claim_run(runs: Vec<RunSummary>, dispatched_at: u64, claimed: &Mutex<HashSet<u64>>) -> Option<RunSummary> {
+    let mut claimed = claimed.lock().unwrap_or_else(|e| e.into_inner());
+    // `gh run list` is newest first
+    let ours = runs.into_iter().rev().find(|r| {
+        !claimed.contains(&r.id)
+            && parse_timestamp(&r.created_at).is_some_and(|t| t + CLOCK_SKEW_SECS >= dispatched_at)
+    })?;
+    claimed.insert(ours.id);
+    Some(ours)
+}
+
+/// Parse GitHub's RFC 3339 timestamps into unix seconds.
+fn parse_timestamp(s: &str) -> Option<u64> {
+    DateTime::parse_from_rfc3339(s).ok().and_then(|t| u64::try_from(t.timestamp()).ok())
+}
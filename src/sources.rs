@@ -0,0 +1,104 @@
+use crate::config::{GitHubSource, InputSourceConfig};
+use crate::guardrails::glob_match;
+use crate::hooks::render_template;
+use crate::service::github::GitHubService;
+
+/// Where an input is being dispatched, for matching and for commands.
+#[derive(Debug, Clone)]
+pub struct SourceContext<'a> {
+    pub repo: &'a str,
+    pub git_ref: &'a str,
+    pub workflow: &'a str,
+    pub branches: &'a [String], // already loaded for the repo
+}
+
+/// The first source configured for an input of a workflow.
+pub fn find<'a>(
+    sources: &'a [InputSourceConfig],
+    ctx: &SourceContext,
+    input: &str,
+) -> Option<&'a InputSourceConfig> {
+    sources.iter().find(|source| {
+        source.repo.as_ref().is_none_or(|r| r == ctx.repo)
+            && source.workflow.as_ref().is_none_or(|w| w == ctx.workflow)
+            && glob_match(&source.input, input)
+    })
+}
+
+/// Short label for the inputs popup, e.g. "github releases".
+pub fn describe(source: &InputSourceConfig) -> String {
+    match (&source.github, source.command.first()) {
+        (Some(kind), _) => format!("github {}", github_name(*kind)),
+        (None, Some(program)) => format!("command {}", program),
+        (None, None) => "config list".to_string(),
+    }
+}
+
+/// The values a source offers, in its own order, without blanks or repeats.
+pub fn resolve(
+    source: &InputSourceConfig,
+    ctx: &SourceContext,
+    input: &str,
+    github: &GitHubService,
+) -> Result<Vec<String>, String> {
+    let values = match (&source.github, source.command.split_first()) {
+        (Some(kind), _) => fetch_github(*kind, ctx, github)?,
+        (None, Some((program, args))) => run_command(program, args, ctx, input)?,
+        (None, None) => source.values.clone(),
+    };
+
+    let mut unique: Vec<String> = Vec::new();
+    for value in values {
+        let value = value.trim().to_string();
+        if !value.is_empty() && !unique.contains(&value) {
+            unique.push(value);
+        }
+    }
+    Ok(unique)
+}
+
+fn github_name(kind: GitHubSource) -> &'static str {
+    match kind {
+        GitHubSource::Tags => "tags",
+        GitHubSource::Releases => "releases",
+        GitHubSource::Branches => "branches",
+        GitHubSource::Environments => "environments",
+    }
+}
+
+fn fetch_github(kind: GitHubSource, ctx: &SourceContext, github: &GitHubService) -> Result<Vec<String>, String> {
+    let result = match kind {
+        GitHubSource::Tags => github.list_recent_tags(ctx.repo),
+        GitHubSource::Releases => github.list_release_tags(ctx.repo),
+        GitHubSource::Branches => return Ok(ctx.branches.to_vec()),
+        GitHubSource::Environments => github.list_environments(ctx.repo),
+    };
+    result.map_err(|e| e.to_string())
+}
+
+/// Run a source command without a shell; every output line is a value.
+/// Arguments may use `{repo}`, `{ref}`, `{workflow}` and `{input}`, which
+/// are also passed as `DISPATCHRR_*` environment variables.
+fn run_command(program: &str, args: &[String], ctx: &SourceContext, input: &str) -> Result<Vec<String>, String> {
+    let fields = [
+        ("repo", ctx.repo.to_string()),
+        ("ref", ctx.git_ref.to_string()),
+        ("workflow", ctx.workflow.to_string()),
+        ("input", input.to_string()),
+    ];
+    let args: Vec<String> = args
+        .iter()
+        .map(|a| render_template(a, &fields, |v| v.to_string()))
+        .collect();
+    let output = std::process::Command::new(program)
+        .args(&args)
+        .envs(fields.iter().map(|(k, v)| (format!("DISPATCHRR_{}", k.to_uppercase()), v.clone())))
+        .stdin(std::process::Stdio::null())
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("exited with {}: {}", output.status, stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).lines().map(str::to_string).collect())
+}
@@ -0,0 +1,274 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use ratatui::style::{Color, Modifier, Style};
+
+/// A built-in color scheme, picked with `theme.name` in the config.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeName {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+/// A themable color. Names are the keys of `theme.colors` in the config.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeSlot {
+    Title,
+    Text,
+    Muted,
+    Border,
+    Focus,
+    Popup,
+    Accent,
+    Heading,
+    Key,
+    Selection,
+    Input,
+    Success,
+    Warning,
+    Error,
+    Info,
+    Backdrop,
+    LogError,
+    LogWarning,
+    LogNotice,
+    LogDebug,
+}
+
+/// Colors used by the UI, by meaning rather than by hue.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub no_color: bool,   // NO_COLOR is set: rely on bold/reverse instead of colors
+    pub title: Color,     // app title
+    pub text: Color,      // regular text in popups
+    pub muted: Color,     // hints, metadata, disabled entries
+    pub border: Color,    // unfocused panels
+    pub focus: Color,     // focused panel border and selection
+    pub popup: Color,     // popup borders
+    pub accent: Color,    // inputs popup border
+    pub heading: Color,   // section headings, group headers
+    pub key: Color,       // key names in help texts
+    pub selection: Color, // selected entry in popups
+    pub input: Color,     // text being typed
+    pub success: Color,
+    pub warning: Color,
+    pub error: Color,
+    pub info: Color,
+    pub backdrop: Color,  // background behind an open popup
+    pub log_error: Color, // run log lines by level
+    pub log_warning: Color,
+    pub log_notice: Color,
+    pub log_debug: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::builtin(ThemeName::Dark)
+    }
+}
+
+impl Theme {
+    pub fn builtin(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Dark => Self {
+                no_color: false,
+                title: Color::LightRed,
+                text: Color::White,
+                muted: Color::DarkGray,
+                border: Color::Gray,
+                focus: Color::Blue,
+                popup: Color::LightCyan,
+                accent: Color::LightMagenta,
+                heading: Color::Yellow,
+                key: Color::LightCyan,
+                selection: Color::LightCyan,
+                input: Color::Green,
+                success: Color::Green,
+                warning: Color::Yellow,
+                error: Color::LightRed,
+                info: Color::LightBlue,
+                backdrop: Color::Rgb(20, 20, 20),
+                log_error: Color::LightRed,
+                log_warning: Color::Yellow,
+                log_notice: Color::LightBlue,
+                log_debug: Color::DarkGray,
+            },
+            ThemeName::Light => Self {
+                no_color: false,
+                title: Color::Red,
+                text: Color::Black,
+                muted: Color::Gray,
+                border: Color::DarkGray,
+                focus: Color::Blue,
+                popup: Color::Cyan,
+                accent: Color::Magenta,
+                heading: Color::Rgb(175, 95, 0),
+                key: Color::Blue,
+                selection: Color::Blue,
+                input: Color::Green,
+                success: Color::Green,
+                warning: Color::Rgb(175, 95, 0),
+                error: Color::Red,
+                info: Color::Blue,
+                backdrop: Color::Rgb(225, 225, 225),
+                log_error: Color::Red,
+                log_warning: Color::Rgb(175, 95, 0),
+                log_notice: Color::Blue,
+                log_debug: Color::Gray,
+            },
+            ThemeName::HighContrast => Self {
+                no_color: false,
+                title: Color::LightYellow,
+                text: Color::White,
+                muted: Color::Gray,
+                border: Color::White,
+                focus: Color::LightYellow,
+                popup: Color::LightCyan,
+                accent: Color::LightMagenta,
+                heading: Color::LightYellow,
+                key: Color::LightCyan,
+                selection: Color::LightYellow,
+                input: Color::LightGreen,
+                success: Color::LightGreen,
+                warning: Color::LightYellow,
+                error: Color::LightRed,
+                info: Color::LightCyan,
+                backdrop: Color::Black,
+                log_error: Color::LightRed,
+                log_warning: Color::LightYellow,
+                log_notice: Color::LightCyan,
+                log_debug: Color::Gray,
+            },
+        }
+    }
+
+    /// Every slot set to the terminal's default color.
+    fn monochrome() -> Self {
+        Self {
+            no_color: true,
+            title: Color::Reset,
+            text: Color::Reset,
+            muted: Color::Reset,
+            border: Color::Reset,
+            focus: Color::Reset,
+            popup: Color::Reset,
+            accent: Color::Reset,
+            heading: Color::Reset,
+            key: Color::Reset,
+            selection: Color::Reset,
+            input: Color::Reset,
+            success: Color::Reset,
+            warning: Color::Reset,
+            error: Color::Reset,
+            info: Color::Reset,
+            backdrop: Color::Reset,
+            log_error: Color::Reset,
+            log_warning: Color::Reset,
+            log_notice: Color::Reset,
+            log_debug: Color::Reset,
+        }
+    }
+
+    /// The configured theme with its color overrides applied. `no_color`
+    /// (the `NO_COLOR` convention) replaces the built-in colors, but colors
+    /// set explicitly in the config are still used.
+    pub fn from_config(name: ThemeName, colors: &BTreeMap<ThemeSlot, String>, no_color: bool) -> Result<Self, Vec<String>> {
+        let mut theme = if no_color { Self::monochrome() } else { Self::builtin(name) };
+        let mut problems = Vec::new();
+        for (&slot, value) in colors {
+            match Color::from_str(value) {
+                Ok(color) => *theme.slot_mut(slot) = color,
+                Err(_) => problems.push(format!("{}: unknown color '{}'", slot_name(slot), value)),
+            }
+        }
+        if problems.is_empty() { Ok(theme) } else { Err(problems) }
+    }
+
+    fn slot_mut(&mut self, slot: ThemeSlot) -> &mut Color {
+        match slot {
+            ThemeSlot::Title => &mut self.title,
+            ThemeSlot::Text => &mut self.text,
+            ThemeSlot::Muted => &mut self.muted,
+            ThemeSlot::Border => &mut self.border,
+            ThemeSlot::Focus => &mut self.focus,
+            ThemeSlot::Popup => &mut self.popup,
+            ThemeSlot::Accent => &mut self.accent,
+            ThemeSlot::Heading => &mut self.heading,
+            ThemeSlot::Key => &mut self.key,
+            ThemeSlot::Selection => &mut self.selection,
+            ThemeSlot::Input => &mut self.input,
+            ThemeSlot::Success => &mut self.success,
+            ThemeSlot::Warning => &mut self.warning,
+            ThemeSlot::Error => &mut self.error,
+            ThemeSlot::Info => &mut self.info,
+            ThemeSlot::Backdrop => &mut self.backdrop,
+            ThemeSlot::LogError => &mut self.log_error,
+            ThemeSlot::LogWarning => &mut self.log_warning,
+            ThemeSlot::LogNotice => &mut self.log_notice,
+            ThemeSlot::LogDebug => &mut self.log_debug,
+        }
+    }
+
+    pub fn fg(&self, color: Color) -> Style {
+        Style::default().fg(color)
+    }
+
+    /// Panel border, highlighted when the panel has focus.
+    pub fn panel_border(&self, focused: bool) -> Style {
+        if focused { self.fg(self.focus) } else { self.fg(self.border) }
+    }
+
+    /// Selected entry of a panel list.
+    pub fn panel_highlight(&self, focused: bool) -> Style {
+        if focused {
+            self.fg(self.focus).add_modifier(Modifier::BOLD)
+        } else {
+            self.fg(self.border)
+        }
+    }
+
+    /// Selected entry of a popup list, drawn as a filled bar.
+    pub fn selection_bar(&self) -> Style {
+        if self.no_color {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default().fg(Color::Black).bg(self.selection)
+        }
+    }
+
+    /// Label on a filled background, e.g. the DRY RUN title badge.
+    pub fn badge(&self, color: Color) -> Style {
+        let style = if self.no_color {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default().fg(Color::Black).bg(color)
+        };
+        style.add_modifier(Modifier::BOLD)
+    }
+
+    /// Color of a run log line, from its GitHub Actions log command.
+    pub fn log_line(&self, line: &str) -> Style {
+        // `::error` workflow commands show up as `##[error]` in the log
+        let level = line.split_once("##[").map(|(_, rest)| rest);
+        match level {
+            Some(rest) if rest.starts_with("error") => self.fg(self.log_error),
+            Some(rest) if rest.starts_with("warning") => self.fg(self.log_warning),
+            Some(rest) if rest.starts_with("notice") => self.fg(self.log_notice),
+            Some(rest) if rest.starts_with("debug") => self.fg(self.log_debug),
+            _ => Style::default(),
+        }
+    }
+}
+
+/// Whether the `NO_COLOR` convention (https://no-color.org) asks for no colors.
+pub fn no_color_requested() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())
+}
+
+fn slot_name(slot: ThemeSlot) -> String {
+    serde_yaml::to_string(&slot).unwrap_or_default().trim().to_string()
+}
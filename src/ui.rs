@@ -1,4 +1,9 @@
-use crate::app::AppState;
+use crate::app::{AppState, RepoField, RepoRow};
+use crate::config::ConfigSource;
+use crate::input_rules::InputSort;
+use crate::keymap::{Action, Keymap};
+use crate::theme::Theme;
+use crate::widgets::TextInput;
 use ratatui::{
     prelude::*,
     widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
@@ -6,6 +11,53 @@ use ratatui::{
 
 const REPO_HIGHLIGHT_SYMBOL: &str = ">> ";
 
+/// Help popup sections in display order.
+const HELP_SECTIONS: &[&str] = &["General", "Repos Panel", "Inputs Popup", "Replays Popup", "After Dispatch"];
+
+/// Panel title while searching: the panel name followed by the query being typed.
+fn search_title(name: &str, query: &mut TextInput, panel_width: u16) -> Line<'static> {
+    let prefix = format!("{} /", name);
+    // The title sits between the two corners of the border
+    let width = panel_width.saturating_sub(prefix.len() as u16 + 2);
+    let mut title = query.line(width, Style::default(), true);
+    title.spans.insert(0, Span::raw(prefix));
+    title
+}
+
+/// Help popup contents, built from the active keymap so rebound keys show up.
+fn help_lines(keymap: &Keymap, theme: &Theme) -> Vec<Line<'static>> {
+    let heading = theme.fg(theme.heading).add_modifier(Modifier::BOLD);
+    let key = theme.fg(theme.key);
+    let entry = |keys: String, description: &str| {
+        Line::from(vec![
+            Span::styled(format!("  {:<18}", keys), key),
+            Span::raw(description.to_string()),
+        ])
+    };
+
+    let mut lines = Vec::new();
+    for &section in HELP_SECTIONS {
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled(format!("── {} ──", section), heading)));
+        // Keys that aren't actions of their own
+        match section {
+            "Repos Panel" => lines.push(entry(
+                format!("{} (on group)", keymap.label(Action::Select)),
+                "Expand / collapse the group",
+            )),
+            "Inputs Popup" => lines.push(entry("Tab / Shift+Tab".to_string(), "Cycle choice or sourced options")),
+            "Replays Popup" => lines.push(entry("Enter".to_string(), "Run selected replay")),
+            _ => {}
+        }
+        for &action in Action::ALL.iter().filter(|a| a.help_section() == section) {
+            lines.push(entry(keymap.labels(action), action.description()));
+        }
+    }
+    lines
+}
+
 fn slice_with_offset(text: &str, offset: usize, width: usize) -> String {
     if width == 0 {
         return String::new();
@@ -14,6 +66,18 @@ fn slice_with_offset(text: &str, offset: usize, width: usize) -> String {
     text.chars().skip(offset).take(width).collect()
 }
 
+/// A repo's line in the Repos panel: favorites get a star, grouped repos are
+/// indented under their header.
+fn repo_row_text(repo: &crate::domain::Repo) -> String {
+    if repo.favorite {
+        format!("★ {}", repo.label())
+    } else if repo.group.is_some() {
+        format!("  {}", repo.label())
+    } else {
+        repo.label()
+    }
+}
+
 fn clamp_scroll_offset(offset: u16, text_len: usize, visible_width: u16) -> u16 {
     if visible_width == 0 {
         return 0;
@@ -32,7 +96,7 @@ fn wrapped_line_count(text: &str, width: u16) -> u16 {
     text.lines()
         .map(|line| {
             let len = line.chars().count();
-            let mut count = (len + width - 1) / width;
+            let mut count = len.div_ceil(width);
             if count == 0 {
                 count = 1;
             }
@@ -53,7 +117,7 @@ where
         .into_iter()
         .map(|line| {
             let len = line.chars().count();
-            let mut count = (len + width as usize - 1) / width as usize;
+            let mut count = len.div_ceil(width as usize);
             if count == 0 {
                 count = 1;
             }
@@ -63,6 +127,8 @@ where
 }
 
 pub fn render(frame: &mut Frame, state: &mut AppState) {
+    let theme = state.theme.clone();
+
     // Top-level vertical layout: title, main, bottom
     let main_layout = Layout::vertical([
         Constraint::Length(1), // Title bar
@@ -71,9 +137,23 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
     ])
     .split(frame.area());
 
-    // Title
-    let title = Paragraph::new("Lazy-Dispatchrr")
-        .style(Color::LightRed)
+    // Title, with a badge when dispatches are only recorded
+    let mut title_spans = vec![Span::styled("Lazy-Dispatchrr", theme.fg(theme.title))];
+    if state.github.is_dry_run() {
+        title_spans.push(Span::raw("  "));
+        title_spans.push(Span::styled(
+            " DRY RUN ",
+            theme.badge(theme.warning),
+        ));
+    }
+    if state.config_read_only {
+        title_spans.push(Span::raw("  "));
+        title_spans.push(Span::styled(
+            " READ-ONLY CONFIG ",
+            theme.badge(theme.error),
+        ));
+    }
+    let title = Paragraph::new(Line::from(title_spans))
         .alignment(Alignment::Center)
         .block(Block::default());
     frame.render_widget(title, main_layout[0]);
@@ -90,23 +170,24 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
     ])
     .split(areas[0]);
 
-    // 1) Repos list (left-most)
-    let repos_highlight = if matches!(state.ui.focus, crate::app::Focus::Repo) {
-        Style::default().add_modifier(Modifier::BOLD).fg(Color::Blue)
-    } else {
-        Style::default().fg(Color::Gray)
-    };
-    let repos_border = if matches!(state.ui.focus, crate::app::Focus::Repo) {
-        Style::default().fg(Color::Blue)
-    } else {
-        Style::default().fg(Color::Gray)
+    // Remember where the panels are for mouse clicks; popups add their own areas below
+    state.ui.hit_areas = crate::app::HitAreas {
+        repos: left_columns[0],
+        branches: left_columns[1],
+        workflows: left_columns[2],
+        output: areas[1],
+        ..Default::default()
     };
+
+    // 1) Repos list (left-most)
+    let repos_highlight = theme.panel_highlight(matches!(state.ui.focus, crate::app::Focus::Repo));
+    let repos_border = theme.panel_border(matches!(state.ui.focus, crate::app::Focus::Repo));
     let repos_title = if state.ui.search_active && matches!(state.ui.focus, crate::app::Focus::Repo) {
-        format!("Repos /{}█", state.ui.search_query)
+        search_title("Repos", &mut state.ui.search_query, left_columns[0].width)
     } else if state.ui.filtered_repo_indices.len() < state.data.repos.len() {
-        format!("Repos [{}/{}]", state.ui.filtered_repo_indices.len(), state.data.repos.len())
+        Line::from(format!("Repos [{}/{}]", state.ui.filtered_repo_indices.len(), state.data.repos.len()))
     } else {
-        "Repos".to_string()
+        Line::from("Repos")
     };
     let repos_block = Block::default()
         .title(repos_title)
@@ -118,21 +199,27 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
     let selected_repo_len = state
         .selected_repo_real_index()
         .and_then(|i| state.data.repos.get(i))
-        .map(|r| r.name.chars().count())
+        .map(|r| repo_row_text(r).chars().count())
         .unwrap_or(0);
     state.ui.repos_hscroll =
         clamp_scroll_offset(state.ui.repos_hscroll, selected_repo_len, visible_width);
     let repo_items: Vec<ListItem> = state
-        .ui.filtered_repo_indices
+        .ui.repo_rows
         .iter()
-        .filter_map(|&i| state.data.repos.get(i))
-        .map(|r| {
-            let visible = slice_with_offset(
-                &r.name,
-                state.ui.repos_hscroll as usize,
-                visible_width as usize,
-            );
-            ListItem::new(visible)
+        .map(|row| match row {
+            RepoRow::Group { name, count, collapsed } => {
+                let marker = if *collapsed { "▸" } else { "▾" };
+                ListItem::new(format!("{} {} ({})", marker, name, count))
+                    .style(theme.fg(theme.heading).add_modifier(Modifier::BOLD))
+            }
+            RepoRow::Repo(i) => {
+                let text = state.data.repos.get(*i).map(repo_row_text).unwrap_or_default();
+                ListItem::new(slice_with_offset(
+                    &text,
+                    state.ui.repos_hscroll as usize,
+                    visible_width as usize,
+                ))
+            }
         })
         .collect();
     let repos_list = List::new(repo_items)
@@ -148,22 +235,14 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
         .filter_map(|&i| state.data.branches.get(i))
         .map(|b| ListItem::new(b.clone()))
         .collect();
-    let branches_highlight = if matches!(state.ui.focus, crate::app::Focus::Branches) {
-        Style::default().add_modifier(Modifier::BOLD).fg(Color::Blue)
-    } else {
-        Style::default().fg(Color::Gray)
-    };
-    let branches_border = if matches!(state.ui.focus, crate::app::Focus::Branches) {
-        Style::default().fg(Color::Blue)
-    } else {
-        Style::default().fg(Color::Gray)
-    };
+    let branches_highlight = theme.panel_highlight(matches!(state.ui.focus, crate::app::Focus::Branches));
+    let branches_border = theme.panel_border(matches!(state.ui.focus, crate::app::Focus::Branches));
     let branches_title = if state.ui.search_active && matches!(state.ui.focus, crate::app::Focus::Branches) {
-        format!("Branches /{}█", state.ui.search_query)
+        search_title("Branches", &mut state.ui.search_query, left_columns[1].width)
     } else if state.ui.filtered_branch_indices.len() < state.data.branches.len() {
-        format!("Branches [{}/{}]", state.ui.filtered_branch_indices.len(), state.data.branches.len())
+        Line::from(format!("Branches [{}/{}]", state.ui.filtered_branch_indices.len(), state.data.branches.len()))
     } else {
-        "Branches".to_string()
+        Line::from("Branches")
     };
     let branches_list = List::new(branch_items)
         .block(Block::default().title(branches_title).borders(Borders::ALL).border_style(branches_border))
@@ -178,22 +257,14 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
         .filter_map(|&i| state.data.workflows.get(i))
         .map(|w| ListItem::new(w.name.clone()))
         .collect();
-    let workflows_highlight = if matches!(state.ui.focus, crate::app::Focus::Workflows) {
-        Style::default().add_modifier(Modifier::BOLD).fg(Color::Blue)
-    } else {
-        Style::default().fg(Color::Gray)
-    };
-    let workflows_border = if matches!(state.ui.focus, crate::app::Focus::Workflows) {
-        Style::default().fg(Color::Blue)
-    } else {
-        Style::default().fg(Color::Gray)
-    };
+    let workflows_highlight = theme.panel_highlight(matches!(state.ui.focus, crate::app::Focus::Workflows));
+    let workflows_border = theme.panel_border(matches!(state.ui.focus, crate::app::Focus::Workflows));
     let workflows_title = if state.ui.search_active && matches!(state.ui.focus, crate::app::Focus::Workflows) {
-        format!("Workflows /{}█", state.ui.search_query)
+        search_title("Workflows", &mut state.ui.search_query, left_columns[2].width)
     } else if state.ui.filtered_workflow_indices.len() < state.data.workflows.len() {
-        format!("Workflows [{}/{}]", state.ui.filtered_workflow_indices.len(), state.data.workflows.len())
+        Line::from(format!("Workflows [{}/{}]", state.ui.filtered_workflow_indices.len(), state.data.workflows.len()))
     } else {
-        "Workflows".to_string()
+        Line::from("Workflows")
     };
     let workflows_list = List::new(workflow_items)
         .block(Block::default().title(workflows_title).borders(Borders::ALL).border_style(workflows_border))
@@ -202,11 +273,7 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
     frame.render_stateful_widget(workflows_list, left_columns[2], &mut state.ui.workflows_state);
 
     // Right area: big output panel (75% width)
-    let output_border = if matches!(state.ui.focus, crate::app::Focus::Output) {
-        Style::default().fg(Color::Yellow)
-    } else {
-        Style::default().fg(Color::Gray)
-    };
+    let output_border = theme.panel_border(matches!(state.ui.focus, crate::app::Focus::Output));
 
     let output_block = Block::default()
         .title("Output")
@@ -221,14 +288,15 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
         let lines: Vec<Line> = state
             .ui.dispatch_output_lines
             .iter()
-            .map(|(text, color)| {
-                let fg = match color {
-                    crate::app::DispatchOutputColor::Green => Color::Green,
-                    crate::app::DispatchOutputColor::Yellow => Color::Yellow,
-                    crate::app::DispatchOutputColor::White => Color::White,
-                    crate::app::DispatchOutputColor::Blue => Color::LightBlue,
+            .map(|(text, tone)| {
+                let fg = match tone {
+                    crate::app::OutputTone::Success => theme.success,
+                    crate::app::OutputTone::Warning => theme.warning,
+                    crate::app::OutputTone::Normal => theme.text,
+                    crate::app::OutputTone::Info => theme.info,
+                    crate::app::OutputTone::Error => theme.error,
                 };
-                Line::from(Span::styled(text.clone(), Style::default().fg(fg)))
+                Line::from(Span::styled(text.clone(), theme.fg(fg)))
             })
             .collect();
         let total_lines = wrapped_lines_total(
@@ -247,16 +315,19 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
             .ui.output
             .clone()
             .unwrap_or_else(|| "No output yet.".to_string());
-        let output_style = if state.ui.output_is_error {
-            Style::default().fg(Color::Red)
-        } else {
-            Style::default()
-        };
         let total_lines = wrapped_line_count(&output_text, output_visible_width);
         let max_scroll = total_lines.saturating_sub(output_visible_height);
         state.ui.output_scroll = state.ui.output_scroll.min(max_scroll);
+        // Errors are drawn in one color; anything else may be a run log
+        let output_text = if state.ui.output_is_error {
+            Text::styled(output_text, theme.fg(theme.error))
+        } else {
+            output_text
+                .lines()
+                .map(|line| Line::styled(line.to_string(), theme.log_line(line)))
+                .collect()
+        };
         let output_paragraph = Paragraph::new(output_text)
-            .style(output_style)
             .block(output_block.clone())
             .wrap(Wrap { trim: true })
             .scroll((state.ui.output_scroll, 0));
@@ -264,7 +335,22 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
     }
 
     // Bottom help bar
-    let help_text = "Tab: focus | j/k: nav | h/l: repo scroll | j/k: output scroll | /: search | r: replays | ?: help | q: quit";
+    let keymap = &state.keymap;
+    let help_text = format!(
+        "{}: focus | {}/{}: nav | {}/{}: repo scroll | {}/{}: output scroll | {}: search | {}: commands | {}: replays | {}: help | {}: quit",
+        keymap.label(Action::NextPanel),
+        keymap.label(Action::Down),
+        keymap.label(Action::Up),
+        keymap.label(Action::ScrollLeft),
+        keymap.label(Action::ScrollRight),
+        keymap.label(Action::Down),
+        keymap.label(Action::Up),
+        keymap.label(Action::Search),
+        keymap.label(Action::CommandPalette),
+        keymap.label(Action::Replays),
+        keymap.label(Action::Help),
+        keymap.label(Action::Quit),
+    );
     let help_paragraph = Paragraph::new(help_text).block(Block::default());
     frame.render_widget(help_paragraph, main_layout[2]);
 
@@ -273,15 +359,25 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
         || state.ui.show_inputs_popup
         || state.ui.show_confirm_dispatch
         || state.ui.show_help_popup
-        || state.ui.show_replays_popup;
+        || state.ui.show_replays_popup
+        || state.ui.show_failures_popup
+        || state.ui.confirm_remove_repo.is_some()
+        || state.ui.repo_edit.is_some()
+        || state.ui.repo_import.is_some()
+        || state.ui.command_palette.is_some()
+        || state.ui.config_error.is_some();
     if any_popup_active {
         let area = frame.area();
         let buf = frame.buffer_mut();
         for y in area.top()..area.bottom() {
             for x in area.left()..area.right() {
                 if let Some(cell) = buf.cell_mut((x, y)) {
-                    cell.set_fg(Color::DarkGray);
-                    cell.set_bg(Color::Rgb(20, 20, 20));
+                    if theme.no_color {
+                        cell.modifier.insert(Modifier::DIM);
+                    } else {
+                        cell.set_fg(theme.muted);
+                        cell.set_bg(theme.backdrop);
+                    }
                 }
             }
         }
@@ -290,10 +386,12 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
     // Add Repo popup
     if state.ui.show_add_repo_popup {
         let area = frame.area();
+        let suggestions = state.add_repo_suggestions();
+        let problem = state.add_repo_problem();
         let popup_v = Layout::vertical([
-            Constraint::Percentage(35),
-            Constraint::Length(8),
-            Constraint::Percentage(35),
+            Constraint::Percentage(30),
+            Constraint::Length(7 + suggestions.len() as u16),
+            Constraint::Percentage(30),
         ])
         .split(area);
 
@@ -308,9 +406,9 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
         frame.render_widget(Clear, popup_area);
 
         let popup_block = Block::default()
-            .title(" Add Repo (Tab: switch field, Enter: submit, Esc: cancel) ")
+            .title(" Add Repo (Tab: complete/next field, ↑/↓: suggestions, Enter: submit, Esc: cancel) ")
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::LightCyan));
+            .border_style(theme.fg(theme.popup));
 
         let inner = popup_block.inner(popup_area);
         frame.render_widget(popup_block, popup_area);
@@ -319,34 +417,306 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
             Constraint::Length(1), // owner label + input
             Constraint::Length(1), // spacer
             Constraint::Length(1), // repo label + input
+            Constraint::Length(2), // validation / hint
+            Constraint::Min(0),    // suggestions
         ])
         .split(inner);
 
         let owner_style = if state.ui.add_repo_focus_owner {
-            Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
+            theme.fg(theme.input).add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::DarkGray)
+            theme.fg(theme.muted)
         };
         let repo_style = if !state.ui.add_repo_focus_owner {
-            Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
+            theme.fg(theme.input).add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::DarkGray)
+            theme.fg(theme.muted)
         };
 
-        let cursor = "█";
-        let owner_text = if state.ui.add_repo_focus_owner {
-            format!("Owner: {}{}", state.ui.add_repo_owner, cursor)
-        } else {
-            format!("Owner: {}", state.ui.add_repo_owner)
+        let label_width = "Owner: ".len() as u16;
+        let field_width = fields[0].width.saturating_sub(label_width);
+        let mut owner_line = state.ui.add_repo_owner.line(field_width, owner_style, state.ui.add_repo_focus_owner);
+        owner_line.spans.insert(0, Span::styled("Owner: ", owner_style));
+        let mut repo_line = state.ui.add_repo_name.line(field_width, repo_style, !state.ui.add_repo_focus_owner);
+        repo_line.spans.insert(0, Span::styled("Repo:  ", repo_style));
+
+        frame.render_widget(Paragraph::new(owner_line), fields[0]);
+        frame.render_widget(Paragraph::new(repo_line), fields[2]);
+
+        let hint = match problem {
+            Some(problem) => Span::styled(format!("⚠ {}", problem), theme.fg(theme.error)),
+            None => Span::styled(
+                "Paste owner/repo or a github.com URL into either field",
+                theme.fg(theme.muted),
+            ),
         };
-        let repo_text = if !state.ui.add_repo_focus_owner {
-            format!("Repo:  {}{}", state.ui.add_repo_name, cursor)
+        frame.render_widget(Paragraph::new(Line::from(hint)), fields[3]);
+
+        let suggestion_items: Vec<ListItem> = suggestions
+            .iter()
+            .map(|s| ListItem::new(format!("  {}", s)))
+            .collect();
+        let mut suggestion_state = ratatui::widgets::ListState::default();
+        suggestion_state.select(Some(state.ui.add_repo_suggestion.min(suggestions.len().saturating_sub(1))));
+        let suggestion_list = List::new(suggestion_items)
+            .highlight_style(theme.selection_bar());
+        frame.render_stateful_widget(suggestion_list, fields[4], &mut suggestion_state);
+    }
+
+    // Bulk-import popup
+    let mut import_list_state = None;
+    if let Some(import) = &state.ui.repo_import {
+        let area = frame.area();
+        let fetched = import.listings.is_some();
+        let popup_height = if fetched { area.height.saturating_sub(6).max(10) } else { 5 };
+
+        let popup_v = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(popup_height),
+            Constraint::Min(0),
+        ])
+        .split(area);
+
+        let popup_h = Layout::horizontal([
+            Constraint::Percentage(15),
+            Constraint::Min(60),
+            Constraint::Percentage(15),
+        ])
+        .split(popup_v[1]);
+
+        let popup_area = popup_h[1];
+        frame.render_widget(Clear, popup_area);
+
+        let title = if fetched {
+            " Import Repos (type: filter, ↑/↓: navigate, Space: select, Ctrl+A: all, Enter: import, Esc: cancel) "
         } else {
-            format!("Repo:  {}", state.ui.add_repo_name)
+            " Import Repos (Enter: list repos, Esc: cancel) "
+        };
+        let popup_block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(theme.fg(theme.popup));
+        let inner = popup_block.inner(popup_area);
+        frame.render_widget(popup_block, popup_area);
+
+        match &import.listings {
+            None => {
+                let lines = vec![
+                    Line::from(Span::styled(
+                        format!("Owner: {}█", import.owner),
+                        theme.fg(theme.input).add_modifier(Modifier::BOLD),
+                    )),
+                    Line::from(Span::styled(
+                        "User or organization; leave empty for your own repos",
+                        theme.fg(theme.muted),
+                    )),
+                ];
+                frame.render_widget(Paragraph::new(lines), inner);
+            }
+            Some(listings) => {
+                let rows = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).split(inner);
+                frame.render_widget(
+                    Paragraph::new(format!(
+                        "Filter: {}█   {} selected, {} shown of {}",
+                        import.filter,
+                        import.checked.len(),
+                        import.filtered.len(),
+                        listings.len()
+                    ))
+                    .style(theme.fg(theme.input)),
+                    rows[0],
+                );
+
+                let items: Vec<ListItem> = import
+                    .filtered
+                    .iter()
+                    .map(|&i| {
+                        let listing = &listings[i];
+                        let listed = state.is_listed(&listing.name);
+                        let check = if listed {
+                            "[✓]"
+                        } else if import.checked.contains(&i) {
+                            "[x]"
+                        } else {
+                            "[ ]"
+                        };
+                        let workflows = match listing.dispatchable {
+                            0 => Span::styled(" no dispatchable workflows", theme.fg(theme.muted)),
+                            n => Span::styled(format!(" {} dispatchable", n), theme.fg(theme.success)),
+                        };
+                        let mut spans = vec![
+                            Span::raw(format!("{} ", check)),
+                            Span::styled(listing.name.clone(), Style::default().add_modifier(Modifier::BOLD)),
+                            workflows,
+                        ];
+                        if listed {
+                            spans.push(Span::styled(" (already added)", theme.fg(theme.muted)));
+                        }
+                        if !listing.description.is_empty() {
+                            spans.push(Span::styled(format!("  {}", listing.description), theme.fg(theme.muted)));
+                        }
+                        ListItem::new(Line::from(spans))
+                    })
+                    .collect();
+                let list = List::new(items)
+                    .highlight_symbol(">> ")
+                    .highlight_style(theme.fg(theme.selection));
+                let mut list_state = import.list_state;
+                frame.render_stateful_widget(list, rows[1], &mut list_state);
+                import_list_state = Some(list_state);
+            }
+        }
+    }
+    if let (Some(import), Some(list_state)) = (state.ui.repo_import.as_mut(), import_list_state) {
+        import.list_state = list_state; // keep the scroll offset
+    }
+
+    // Command palette
+    let mut palette_list_state = None;
+    if let Some(palette) = &state.ui.command_palette {
+        let area = frame.area();
+        let popup_height = (palette.filtered.len() as u16 + 4).clamp(6, area.height.saturating_sub(4).max(6));
+
+        let popup_v = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(popup_height),
+            Constraint::Min(0),
+        ])
+        .split(area);
+
+        let popup_h = Layout::horizontal([
+            Constraint::Percentage(20),
+            Constraint::Min(50),
+            Constraint::Percentage(20),
+        ])
+        .split(popup_v[1]);
+
+        let popup_area = popup_h[1];
+        frame.render_widget(Clear, popup_area);
+
+        let popup_block = Block::default()
+            .title(" Command Palette (type: filter, ↑/↓: navigate, Enter: run, Esc: close) ")
+            .borders(Borders::ALL)
+            .border_style(theme.fg(theme.popup));
+        let inner = popup_block.inner(popup_area);
+        frame.render_widget(popup_block, popup_area);
+
+        let rows = Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).split(inner);
+        frame.render_widget(
+            Paragraph::new(format!("> {}█", palette.query)).style(theme.fg(theme.input).add_modifier(Modifier::BOLD)),
+            rows[0],
+        );
+
+        // Labels on the left, bound keys right-aligned
+        let width = rows[1].width.saturating_sub(3) as usize;
+        let items: Vec<ListItem> = palette
+            .filtered
+            .iter()
+            .map(|&i| {
+                let entry = &palette.entries[i];
+                let keys = entry.keys.as_deref().unwrap_or_default();
+                let gap = width.saturating_sub(entry.label.chars().count() + keys.chars().count()).max(1);
+                ListItem::new(Line::from(vec![
+                    Span::raw(entry.label.clone()),
+                    Span::raw(" ".repeat(gap)),
+                    Span::styled(keys.to_string(), theme.fg(theme.key)),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_symbol(">> ")
+            .highlight_style(theme.fg(theme.selection).add_modifier(Modifier::BOLD));
+        let mut list_state = palette.list_state;
+        frame.render_stateful_widget(list, rows[1], &mut list_state);
+        palette_list_state = Some(list_state);
+    }
+    if let (Some(palette), Some(list_state)) = (state.ui.command_palette.as_mut(), palette_list_state) {
+        palette.list_state = list_state; // keep the scroll offset
+    }
+
+    // Repo alias/group popup
+    if let Some(edit) = &state.ui.repo_edit {
+        let area = frame.area();
+        let popup_v = Layout::vertical([
+            Constraint::Percentage(35),
+            Constraint::Length(3),
+            Constraint::Percentage(35),
+        ])
+        .split(area);
+
+        let popup_h = Layout::horizontal([
+            Constraint::Percentage(20),
+            Constraint::Min(50),
+            Constraint::Percentage(20),
+        ])
+        .split(popup_v[1]);
+
+        let popup_area = popup_h[1];
+        frame.render_widget(Clear, popup_area);
+
+        let title = match edit.field {
+            RepoField::Alias => format!(" Alias for {} (Enter: save, empty clears, Esc: cancel) ", edit.repo),
+            RepoField::Group => format!(" Group for {} (Enter: save, empty clears, Esc: cancel) ", edit.repo),
         };
+        let popup_block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(theme.fg(theme.popup));
+        let inner = popup_block.inner(popup_area);
+        frame.render_widget(popup_block, popup_area);
 
-        frame.render_widget(Paragraph::new(owner_text).style(owner_style), fields[0]);
-        frame.render_widget(Paragraph::new(repo_text).style(repo_style), fields[2]);
+        let label = match edit.field {
+            RepoField::Alias => "Alias",
+            RepoField::Group => "Group",
+        };
+        frame.render_widget(
+            Paragraph::new(format!("{}: {}█", label, edit.value))
+                .style(theme.fg(theme.input).add_modifier(Modifier::BOLD)),
+            inner,
+        );
+    }
+
+    // Repo removal confirmation
+    if let Some(repo_name) = &state.ui.confirm_remove_repo {
+        let area = frame.area();
+        let popup_v = Layout::vertical([
+            Constraint::Percentage(35),
+            Constraint::Length(6),
+            Constraint::Percentage(35),
+        ])
+        .split(area);
+
+        let popup_h = Layout::horizontal([
+            Constraint::Percentage(20),
+            Constraint::Min(50),
+            Constraint::Percentage(20),
+        ])
+        .split(popup_v[1]);
+
+        let popup_area = popup_h[1];
+        frame.render_widget(Clear, popup_area);
+
+        let popup_block = Block::default()
+            .title(" Remove Repo ")
+            .borders(Borders::ALL)
+            .border_style(theme.fg(theme.error));
+        let inner = popup_block.inner(popup_area);
+        frame.render_widget(popup_block, popup_area);
+
+        let replays = state.config.repos.iter()
+            .find(|rc| rc.name == *repo_name)
+            .map(|rc| rc.replays.len())
+            .unwrap_or(0);
+        let lines = vec![
+            Line::from(vec![
+                Span::raw("Remove "),
+                Span::styled(repo_name.clone(), Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(format!(" and its {} saved replays?", replays)),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled("y: remove   any other key: cancel", theme.fg(theme.muted))),
+        ];
+        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), inner);
     }
 
     // Inputs popup — uses a scrollable Paragraph instead of per-row Layout
@@ -374,16 +744,37 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
         let popup_area = popup_h[1];
         frame.render_widget(Clear, popup_area);
 
+        // Problems from a blocked dispatch are re-checked so fixed ones go away
+        if !state.ui.input_problems.is_empty() {
+            state.ui.input_problems = state.check_inputs();
+        }
+
         let num_fields = state.data.input_fields.len();
         let title = format!(
-            " Workflow Inputs [{}/{}] (j/k: navigate, Enter: edit, Tab: cycle choice, D: dispatch, S: save replay, Esc: cancel) ",
+            " Workflow Inputs [{}/{}] (j/k: navigate, Enter: edit, {}: $EDITOR, {}/{}: reset, {}: order, Tab: cycle options, {}: dispatch, {}: save replay, Esc: cancel) ",
             state.ui.input_fields_selected + 1,
             num_fields,
+            state.keymap.label(Action::EditInEditor),
+            state.keymap.label(Action::UnsetInput),
+            state.keymap.label(Action::ResetInputs),
+            state.keymap.label(Action::SortInputs),
+            state.keymap.label(Action::Dispatch),
+            state.keymap.label(Action::SaveReplay),
         );
-        let popup_block = Block::default()
+        let mut popup_block = Block::default()
             .title(title)
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::LightMagenta));
+            .border_style(theme.fg(theme.accent));
+        if state.ui.input_sort != InputSort::Declared {
+            popup_block = popup_block.title_bottom(
+                Line::from(format!(" order: {} ", state.ui.input_sort.label()))
+                    .right_aligned(),
+            );
+        }
+        // Problems with the inputs as a whole, e.g. too many of them
+        for problem in state.ui.input_problems.iter().filter(|p| p.input.is_none()) {
+            popup_block = popup_block.title_bottom(Span::styled(format!(" ⚠ {} ", problem.message), theme.fg(theme.error)));
+        }
 
         let inner = popup_block.inner(popup_area);
         frame.render_widget(popup_block, popup_area);
@@ -391,48 +782,148 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
         // Build every field's lines into a single Vec<Line> for a scrollable Paragraph
         let cursor = "█";
         let mut lines: Vec<Line> = Vec::new();
+        let mut option_spans: Vec<(u16, u16, usize, usize)> = Vec::new(); // (x, width, field, option)
+        let mut field_tops: Vec<(usize, u16)> = Vec::new(); // (field, first line) of every shown field
+        let mut current_group: Option<&String> = None;
+
+        let fields = &state.data.input_fields;
+        for (i, field) in fields.iter().enumerate() {
+            // Inputs whose condition doesn't hold are hidden and not sent
+            if !field.is_shown(fields) {
+                continue;
+            }
+            if field.group.as_ref() != current_group {
+                current_group = field.group.as_ref();
+                // Ungrouped inputs after a group get a plain rule so they don't look grouped
+                let heading = match current_group {
+                    Some(group) => format!("── {} ──", group),
+                    None => "────".to_string(),
+                };
+                lines.push(Line::from(Span::styled(heading, theme.fg(theme.accent).add_modifier(Modifier::BOLD))));
+            }
+            field_tops.push((i, lines.len() as u16));
 
-        for (i, field) in state.data.input_fields.iter().enumerate() {
             let is_selected = i == state.ui.input_fields_selected;
             let is_editing = is_selected && state.ui.input_fields_editing;
 
+            // The reason GitHub would reject this field, if a dispatch was blocked
+            let blocked = state.ui.input_problems
+                .iter()
+                .find(|p| p.input.as_ref() == Some(&field.name))
+                .map(|p| p.message.clone());
+
             // Line 1: name + description
             let req_marker = if field.required { " *" } else { "" };
             let name_text = format!("{}{}: {}", field.name, req_marker, field.description);
             let name_style = if is_selected {
-                Style::default().fg(Color::LightCyan).add_modifier(Modifier::BOLD)
+                theme.fg(theme.selection).add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::White)
+                theme.fg(theme.text)
             };
-            lines.push(Line::from(Span::styled(name_text, name_style)));
+            let mut name_spans = vec![Span::styled(name_text, name_style)];
+            // Changed inputs are marked so edits stand out from the defaults
+            if field.differs_from_default() {
+                name_spans.insert(0, Span::styled("● ", theme.fg(theme.warning)));
+            }
+            if blocked.is_some() {
+                name_spans.insert(0, Span::styled("⚠ ", theme.fg(theme.error)));
+            }
+            lines.push(Line::from(name_spans));
 
             // Line 2: type / default / options
             let mut meta_parts = vec![format!("  type: {}", field.input_type)];
             if !field.default_value.is_empty() {
                 meta_parts.push(format!("default: {}", field.default_value));
             }
-            if !field.options.is_empty() {
+            if let Some(source) = &field.source {
+                meta_parts.push(format!("source: {} ({} values)", source, field.options.len()));
+            } else if !field.options.is_empty() {
                 meta_parts.push(format!("options: [{}]", field.options.join(", ")));
             }
+            if field.is_json() {
+                meta_parts.push("JSON".to_string());
+            }
+            if let Some(condition) = &field.show_if {
+                meta_parts.push(format!("when {}", condition));
+            }
             let meta_text = meta_parts.join(" | ");
-            lines.push(Line::from(Span::styled(meta_text, Style::default().fg(Color::DarkGray))));
+            let mut meta_spans = vec![Span::styled(meta_text, theme.fg(theme.muted))];
+            if let Some(problem) = blocked {
+                meta_spans.push(Span::styled(format!("  ⚠ {}", problem), theme.fg(theme.error)));
+            } else if let Some(problem) = field.json_problem() {
+                meta_spans.push(Span::styled(format!("  ⚠ {}", problem), theme.fg(theme.warning)));
+            }
+            lines.push(Line::from(meta_spans));
+
+            // Unset inputs are left out of the dispatch; set ones are sent even when empty
+            let state_marker = match (field.is_set(), field.value.is_empty()) {
+                (false, true) => Some("  (unset)"),
+                (false, false) => Some("  (default, not sent)"),
+                (true, true) => Some("\"\"  (sent empty)"),
+                (true, false) => None,
+            };
 
-            // Line 3: value
-            let val_display = if is_editing {
-                format!("  > {}{}", field.value, cursor)
+            // Line 3: value; choices list every option so they can be clicked
+            if field.input_type == "choice" && !field.options.is_empty() {
+                let prefix = if is_selected { "  > " } else { "    " };
+                let mut spans = vec![Span::styled(prefix, theme.fg(theme.selection))];
+                let mut x = prefix.len() as u16;
+                for (o, option) in field.options.iter().enumerate() {
+                    let span = if *option == field.value {
+                        let style = if is_selected { theme.selection_bar() } else { theme.fg(theme.text) };
+                        Span::styled(format!("[{}]", option), style.add_modifier(Modifier::BOLD))
+                    } else {
+                        Span::styled(format!(" {} ", option), theme.fg(theme.muted))
+                    };
+                    let width = span.width() as u16;
+                    option_spans.push((x, width, i, o));
+                    spans.push(span);
+                    spans.push(Span::raw(" "));
+                    x += width + 1;
+                }
+                if let Some(marker) = state_marker {
+                    spans.push(Span::styled(marker, theme.fg(theme.muted)));
+                }
+                lines.push(Line::from(spans));
+                lines.push(Line::from(""));
+                continue;
+            }
+            let val_style = if is_editing {
+                theme.fg(theme.input).add_modifier(Modifier::BOLD)
             } else if is_selected {
-                format!("  > {}", field.value)
+                theme.fg(theme.selection)
             } else {
-                format!("    {}", field.value)
+                theme.fg(theme.muted)
             };
-            let val_style = if is_editing {
-                Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
+            if is_editing && field.input_type != "boolean" {
+                let mut value_line = state.ui.input_editor.line(inner.width.saturating_sub(4), val_style, true);
+                value_line.spans.insert(0, Span::styled("  > ", val_style));
+                lines.push(value_line);
+                lines.push(Line::from(""));
+                continue;
+            }
+            // Multi-line values from $EDITOR are shown on one line
+            let value = field.value.replace('\n', "⏎");
+            let val_display = if is_editing {
+                format!("  > {}{}", value, cursor)
             } else if is_selected {
-                Style::default().fg(Color::Yellow)
+                format!("  > {}", value)
             } else {
-                Style::default().fg(Color::Gray)
+                format!("    {}", value)
             };
-            lines.push(Line::from(Span::styled(val_display, val_style)));
+            let mut value_spans = vec![Span::styled(val_display, val_style)];
+            if let Some(marker) = state_marker {
+                value_spans.push(Span::styled(marker, theme.fg(theme.muted)));
+            }
+            // Sourced values show where they sit in the list Tab cycles through
+            if field.has_options() && is_selected {
+                let position = match field.options.iter().position(|o| *o == field.value) {
+                    Some(idx) => format!("  ◂ {}/{} ▸", idx + 1, field.options.len()),
+                    None => format!("  ◂ Tab: pick from {} ▸", field.options.len()),
+                };
+                value_spans.push(Span::styled(position, theme.fg(theme.muted)));
+            }
+            lines.push(Line::from(value_spans));
 
             // Line 4: blank spacer between fields
             lines.push(Line::from(""));
@@ -441,7 +932,8 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
         // Scroll so the selected field is always visible.
         // Each field occupies 4 lines (name, meta, value, spacer).
         let lines_per_field: u16 = 4;
-        let selected_top = state.ui.input_fields_selected as u16 * lines_per_field;
+        let top_of = |field: usize| field_tops.iter().find(|(i, _)| *i == field).map(|(_, top)| *top);
+        let selected_top = top_of(state.ui.input_fields_selected).unwrap_or(0);
         let visible_height = inner.height;
         let scroll_offset = if selected_top + lines_per_field <= visible_height {
             0
@@ -452,6 +944,57 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
 
         let paragraph = Paragraph::new(lines).scroll((scroll_offset, 0));
         frame.render_widget(paragraph, inner);
+
+        // Clickable areas of the fields and choice options left on screen
+        let visible = scroll_offset..scroll_offset + visible_height;
+        for &(i, field_top) in &field_tops {
+            let top = field_top.max(visible.start);
+            let bottom = (field_top + lines_per_field).min(visible.end);
+            if top < bottom {
+                let rect = Rect::new(inner.x, inner.y + top - scroll_offset, inner.width, bottom - top);
+                state.ui.hit_areas.input_fields.push((rect, i));
+            }
+        }
+        for (x, width, field, option) in option_spans {
+            let Some(line) = top_of(field).map(|top| top + 2) else {
+                continue;
+            };
+            if visible.contains(&line) && x < inner.width {
+                let rect = Rect::new(inner.x + x, inner.y + line - scroll_offset, width.min(inner.width - x), 1);
+                state.ui.hit_areas.input_options.push((rect, field, option));
+            }
+        }
+
+        // Value suggestions drop down under the field being typed into
+        let suggestions = state.input_suggestions();
+        let value_line = selected_top + 2;
+        if !suggestions.is_empty() && visible.contains(&value_line) {
+            let value_y = inner.y + value_line - scroll_offset;
+            let widest = suggestions.iter().map(|s| s.chars().count()).max().unwrap_or(0) as u16;
+            let width = (widest + 4).max(20).min(inner.width.saturating_sub(4));
+            let height = suggestions.len() as u16 + 2;
+            // Below the value when it fits, above it otherwise
+            let y = if value_y + 1 + height <= inner.bottom() {
+                value_y + 1
+            } else {
+                value_y.saturating_sub(height).max(inner.y)
+            };
+            let area = Rect::new(inner.x + 4, y, width, height.min(inner.height));
+            let items: Vec<ListItem> = suggestions.iter().map(|s| ListItem::new(s.clone())).collect();
+            let mut list_state = ratatui::widgets::ListState::default();
+            list_state.select(Some(state.ui.input_suggestion.min(suggestions.len() - 1)));
+            let list = List::new(items)
+                .block(
+                    Block::default()
+                        .title(" ↑/↓ Tab: use ")
+                        .borders(Borders::ALL)
+                        .border_style(theme.fg(theme.muted)),
+                )
+                .style(theme.fg(theme.text))
+                .highlight_style(theme.selection_bar());
+            frame.render_widget(Clear, area);
+            frame.render_stateful_widget(list, area, &mut list_state);
+        }
     }
 
     // Replays popup
@@ -478,10 +1021,14 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
         let popup_area = popup_h[1];
         frame.render_widget(Clear, popup_area);
 
-        let popup_block = Block::default()
-            .title(" Replays (j/k: navigate, Enter: run, d: delete, Esc: close) ")
+        let mut popup_block = Block::default()
+            .title(format!(" Replays (j/k: navigate, Enter: run, {}: delete, Esc: close) ", state.keymap.label(Action::DeleteReplay)))
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::LightGreen));
+            .border_style(theme.fg(theme.success));
+        if state.data.replays_list.iter().any(|r| r.source == ConfigSource::Team) {
+            popup_block = popup_block
+                .title_bottom(Line::from(" [team] replays are shared and read-only ").right_aligned());
+        }
 
         let inner = popup_block.inner(popup_area);
         frame.render_widget(popup_block, popup_area);
@@ -490,26 +1037,83 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
             .data.replays_list
             .iter()
             .map(|r| {
-                let text = format!("{}  ⟶  {}", r.workflow, r.description);
-                ListItem::new(text)
+                let badge_style = match r.source {
+                    ConfigSource::User => theme.fg(theme.muted),
+                    ConfigSource::Team => theme.fg(theme.info),
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("[{}] ", r.source.label()), badge_style),
+                    Span::raw(format!("{}  ⟶  {}", r.workflow, r.description)),
+                ]))
             })
             .collect();
 
         let replay_list = List::new(replay_items)
+            .highlight_symbol(">> ")
+            .highlight_style(theme.fg(theme.success).add_modifier(Modifier::BOLD));
+        frame.render_stateful_widget(replay_list, inner, &mut state.ui.replays_state);
+    }
+
+    // Failures popup
+    if state.ui.show_failures_popup && !state.data.run_failures.is_empty() {
+        let area = frame.area();
+        let num_failures = state.data.run_failures.len();
+        let popup_height = ((num_failures as u16) + 2).min(area.height.saturating_sub(4));
+
+        let popup_v = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(popup_height),
+            Constraint::Min(0),
+        ])
+        .split(area);
+
+        let popup_h = Layout::horizontal([
+            Constraint::Percentage(10),
+            Constraint::Min(50),
+            Constraint::Percentage(10),
+        ])
+        .split(popup_v[1]);
+
+        let popup_area = popup_h[1];
+        frame.render_widget(Clear, popup_area);
+
+        let popup_block = Block::default()
+            .title(" Why it failed (j/k: navigate, Enter: jump to log, Esc: close) ")
+            .borders(Borders::ALL)
+            .border_style(theme.fg(theme.error));
+
+        let inner = popup_block.inner(popup_area);
+        frame.render_widget(popup_block, popup_area);
+
+        let failure_items: Vec<ListItem> = state
+            .data.run_failures
+            .iter()
+            .map(|f| {
+                let style = if f.log_line.is_some() {
+                    theme.fg(theme.text)
+                } else {
+                    theme.fg(theme.muted)
+                };
+                ListItem::new(crate::app::failure_label(f)).style(style)
+            })
+            .collect();
+
+        let failure_list = List::new(failure_items)
             .highlight_symbol(">> ")
             .highlight_style(
-                Style::default()
-                    .fg(Color::LightGreen)
+                theme.fg(theme.error)
                     .add_modifier(Modifier::BOLD),
             );
-        frame.render_stateful_widget(replay_list, inner, &mut state.ui.replays_state);
+        frame.render_stateful_widget(failure_list, inner, &mut state.ui.failures_state);
     }
 
     // Dispatch confirmation popup
     if state.ui.show_confirm_dispatch {
         let area = frame.area();
+        let guard = state.ui.dispatch_guard.as_ref();
         let cmd_lines = state.ui.dispatch_command_preview.len() as u16 / area.width.saturating_sub(20) + 1;
-        let popup_height = cmd_lines + 8;
+        let change_lines = state.ui.dispatch_changes.len().max(1) as u16 + 2;
+        let popup_height = cmd_lines + change_lines + if guard.is_some() { 11 } else { 8 };
 
         let popup_v = Layout::vertical([
             Constraint::Min(0),
@@ -528,28 +1132,115 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
         let popup_area = popup_h[1];
         frame.render_widget(Clear, popup_area);
 
+        let (popup_title, border_color) = if guard.is_some() {
+            (" Confirm Protected Dispatch ", theme.error)
+        } else {
+            (" Confirm Dispatch ", theme.warning)
+        };
         let popup_block = Block::default()
-            .title(" Confirm Dispatch ")
+            .title(popup_title)
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::LightYellow));
+            .border_style(theme.fg(border_color));
 
         let inner = popup_block.inner(popup_area);
         frame.render_widget(popup_block, popup_area);
 
-        let confirm_text = format!(
-            "Command to run:\n\n  {}\n\n(Y) to confirm  |  any other key to cancel",
-            state.ui.dispatch_command_preview
-        );
-        let confirm_paragraph = Paragraph::new(confirm_text)
+        let mut confirm_lines: Vec<Line> = Vec::new();
+        if let Some(guard) = guard {
+            confirm_lines.push(Line::from(Span::styled(
+                format!(" ⚠ {} ", guard.banner),
+                theme.badge(theme.error),
+            )));
+            confirm_lines.push(Line::from(""));
+        }
+        confirm_lines.push(Line::from("Command to run:"));
+        confirm_lines.push(Line::from(""));
+        confirm_lines.push(Line::from(format!("  {}", state.ui.dispatch_command_preview)));
+        confirm_lines.push(Line::from(""));
+        // Only inputs that differ from the workflow defaults, old value struck out
+        confirm_lines.push(Line::from("Changed inputs:"));
+        if state.ui.dispatch_changes.is_empty() {
+            confirm_lines.push(Line::from(Span::styled("  (none, all workflow defaults)", theme.fg(theme.muted))));
+        }
+        for change in &state.ui.dispatch_changes {
+            let mut spans = vec![Span::raw(format!("  {}: ", change.name))];
+            if let Some(default_value) = &change.default_value {
+                let shown = if default_value.is_empty() { "\"\"" } else { default_value.as_str() };
+                spans.push(Span::styled(shown.to_string(), theme.fg(theme.muted).add_modifier(Modifier::CROSSED_OUT)));
+                spans.push(Span::styled(" → ", theme.fg(theme.muted)));
+            }
+            let shown = if change.value.is_empty() { "\"\"".to_string() } else { change.value.replace('\n', "⏎") };
+            spans.push(Span::styled(shown, theme.fg(theme.warning).add_modifier(Modifier::BOLD)));
+            confirm_lines.push(Line::from(spans));
+        }
+        confirm_lines.push(Line::from(""));
+        match guard {
+            Some(guard) if guard.blocked.is_some() => {
+                confirm_lines.push(Line::from(Span::styled(
+                    format!("Blocked: {}.", guard.blocked.as_deref().unwrap_or_default()),
+                    theme.fg(theme.error).add_modifier(Modifier::BOLD),
+                )));
+                confirm_lines.push(Line::from("Press any key or click Close."));
+            }
+            Some(guard) => {
+                let typed_style = if state.ui.confirm_input == guard.phrase {
+                    theme.fg(theme.success).add_modifier(Modifier::BOLD)
+                } else {
+                    theme.fg(theme.error)
+                };
+                confirm_lines.push(Line::from(vec![
+                    Span::raw("Type "),
+                    Span::styled(guard.phrase.clone(), Style::default().add_modifier(Modifier::BOLD)),
+                    Span::raw(" and press Enter to confirm  |  Esc to cancel"),
+                ]));
+                confirm_lines.push(Line::from(vec![
+                    Span::raw("> "),
+                    Span::styled(format!("{}█", state.ui.confirm_input), typed_style),
+                ]));
+            }
+            None => {
+                confirm_lines.push(Line::from("(Y) to confirm  |  any other key to cancel"));
+            }
+        }
+
+        let rows = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).split(inner);
+        let confirm_paragraph = Paragraph::new(confirm_lines)
             .wrap(Wrap { trim: true })
-            .style(Style::default().fg(Color::White));
-        frame.render_widget(confirm_paragraph, inner);
+            .style(theme.fg(theme.text));
+        frame.render_widget(confirm_paragraph, rows[0]);
+
+        // Buttons on the last line; Confirm is only offered when it would dispatch
+        let can_confirm = match guard {
+            Some(guard) => guard.blocked.is_none() && state.ui.confirm_input == guard.phrase,
+            None => true,
+        };
+        let blocked = guard.is_some_and(|guard| guard.blocked.is_some());
+        let confirm_label = if guard.is_some() { "[ Confirm (Enter) ]" } else { "[ Confirm (y) ]" };
+        let cancel_label = if blocked { "[ Close ]" } else { "[ Cancel (Esc) ]" };
+        let mut buttons = Vec::new();
+        let mut x = rows[1].x;
+        if !blocked {
+            let style = if can_confirm {
+                theme.badge(theme.success)
+            } else {
+                theme.fg(theme.muted)
+            };
+            let width = confirm_label.len() as u16;
+            state.ui.hit_areas.confirm_button = Some(Rect::new(x, rows[1].y, width, 1));
+            buttons.push(Span::styled(confirm_label, style));
+            buttons.push(Span::raw("   "));
+            x += width + 3;
+        }
+        state.ui.hit_areas.cancel_button = Some(Rect::new(x, rows[1].y, cancel_label.len() as u16, 1));
+        buttons.push(Span::styled(cancel_label, theme.fg(theme.text).add_modifier(Modifier::BOLD)));
+        frame.render_widget(Paragraph::new(Line::from(buttons)), rows[1]);
     }
 
     // Help popup
     if state.ui.show_help_popup {
         let area = frame.area();
-        let popup_height = 24_u16.min(area.height.saturating_sub(4));
+        let help_lines = help_lines(&state.keymap, &theme);
+        let popup_height = (help_lines.len() as u16 + 2).min(area.height.saturating_sub(4));
 
         let popup_v = Layout::vertical([
             Constraint::Min(0),
@@ -569,83 +1260,71 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
         frame.render_widget(Clear, popup_area);
 
         let popup_block = Block::default()
-            .title(" Keybindings (? to close) ")
+            .title(format!(" Keybindings ({} to close) ", state.keymap.label(Action::Help)))
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::LightCyan));
+            .border_style(theme.fg(theme.popup));
 
         let inner = popup_block.inner(popup_area);
         frame.render_widget(popup_block, popup_area);
 
-        let help_lines: Vec<Line> = vec![
-            Line::from(Span::styled("── General ──", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
-            Line::from(vec![
-                Span::styled("  Tab / Shift+Tab  ", Style::default().fg(Color::LightCyan)),
-                Span::raw("Cycle focus between panels"),
-            ]),
-            Line::from(vec![
-                Span::styled("  j/k  ↑/↓         ", Style::default().fg(Color::LightCyan)),
-                Span::raw("Navigate lists / scroll output"),
-            ]),
-            Line::from(vec![
-                Span::styled("  h/l  Left/Right  ", Style::default().fg(Color::LightCyan)),
-                Span::raw("Scroll repo names horizontally"),
-            ]),
-            Line::from(vec![
-                Span::styled("  Enter             ", Style::default().fg(Color::LightCyan)),
-                Span::raw("Select / confirm action"),
-            ]),
-            Line::from(vec![
-                Span::styled("  /                 ", Style::default().fg(Color::LightCyan)),
-                Span::raw("Fuzzy search in focused panel"),
-            ]),
-            Line::from(vec![
-                Span::styled("  a                 ", Style::default().fg(Color::LightCyan)),
-                Span::raw("Add a new repo"),
-            ]),
-            Line::from(vec![
-                Span::styled("  v                 ", Style::default().fg(Color::LightCyan)),
-                Span::raw("Open repo in browser"),
-            ]),
-            Line::from(vec![
-                Span::styled("  r                 ", Style::default().fg(Color::LightCyan)),
-                Span::raw("Open saved replays"),
-            ]),
-            Line::from(vec![
-                Span::styled("  i                 ", Style::default().fg(Color::LightCyan)),
-                Span::raw("Edit workflow inputs"),
-            ]),
-            Line::from(vec![
-                Span::styled("  q / Esc           ", Style::default().fg(Color::LightCyan)),
-                Span::raw("Quit"),
-            ]),
+        let help_paragraph = Paragraph::new(help_lines)
+            .wrap(Wrap { trim: true });
+        frame.render_widget(help_paragraph, inner);
+    }
+
+    // Config error screen — shown on top of everything at startup
+    if let Some(error) = &state.ui.config_error {
+        let area = frame.area();
+        let popup_height = 14_u16.min(area.height.saturating_sub(2));
+
+        let popup_v = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(popup_height),
+            Constraint::Min(0),
+        ])
+        .split(area);
+
+        let popup_h = Layout::horizontal([
+            Constraint::Percentage(15),
+            Constraint::Min(50),
+            Constraint::Percentage(15),
+        ])
+        .split(popup_v[1]);
+
+        let popup_area = popup_h[1];
+        frame.render_widget(Clear, popup_area);
+
+        let popup_block = Block::default()
+            .title(" Config Error ")
+            .borders(Borders::ALL)
+            .border_style(theme.fg(theme.error));
+
+        let inner = popup_block.inner(popup_area);
+        frame.render_widget(popup_block, popup_area);
+
+        let error_lines: Vec<Line> = vec![
+            Line::from(Span::styled(
+                "The config file could not be loaded:",
+                theme.fg(theme.text).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(error.clone(), theme.fg(theme.error))),
             Line::from(""),
-            Line::from(Span::styled("── Inputs Popup ──", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
-            Line::from(vec![
-                Span::styled("  D                 ", Style::default().fg(Color::LightCyan)),
-                Span::raw("Dispatch workflow"),
-            ]),
-            Line::from(vec![
-                Span::styled("  S                 ", Style::default().fg(Color::LightCyan)),
-                Span::raw("Save inputs as replay"),
-            ]),
             Line::from(vec![
-                Span::styled("  Tab / Shift+Tab   ", Style::default().fg(Color::LightCyan)),
-                Span::raw("Cycle choice options"),
+                Span::styled("  o  ", theme.fg(theme.key)),
+                Span::raw("Open the file in $EDITOR and retry"),
             ]),
-            Line::from(""),
-            Line::from(Span::styled("── Replays Popup ──", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
             Line::from(vec![
-                Span::styled("  Enter             ", Style::default().fg(Color::LightCyan)),
-                Span::raw("Run selected replay"),
+                Span::styled("  c  ", theme.fg(theme.key)),
+                Span::raw("Continue read-only (nothing is written to the file)"),
             ]),
             Line::from(vec![
-                Span::styled("  d                 ", Style::default().fg(Color::LightCyan)),
-                Span::raw("Delete selected replay"),
+                Span::styled("  q  ", theme.fg(theme.key)),
+                Span::raw("Quit"),
             ]),
         ];
 
-        let help_paragraph = Paragraph::new(help_lines)
-            .wrap(Wrap { trim: true });
-        frame.render_widget(help_paragraph, inner);
+        let error_paragraph = Paragraph::new(error_lines).wrap(Wrap { trim: false });
+        frame.render_widget(error_paragraph, inner);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,203 @@
+use std::fmt;
+
+use crate::config::LimitsConfig;
+use crate::domain::InputField;
+
+/// Something GitHub would reject about the inputs of a dispatch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputProblem {
+    pub input: Option<String>, // the offending input; None when it is about all of them
+    pub message: String,
+}
+
+impl fmt::Display for InputProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.input {
+            Some(name) => write!(f, "Input '{}': {}", name, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// Check the `name=value` pairs a dispatch sends against GitHub's limits
+/// and, when the workflow's declared inputs are known, against their names
+/// and types.
+pub fn check(sent: &[(String, String)], fields: Option<&[InputField]>, limits: &LimitsConfig) -> Vec<InputProblem> {
+    let mut problems = Vec::new();
+
+    if let Some(fields) = fields {
+        for (name, _) in sent {
+            if !fields.iter().any(|f| f.name == *name) {
+                problems.push(InputProblem {
+                    input: Some(name.clone()),
+                    message: "not declared by the workflow on this ref".to_string(),
+                });
+            }
+        }
+        // Inputs hidden by their conditions aren't sent, so their values
+        // can't be wrong, but GitHub still insists on required ones
+        let applied = with_values(fields, sent);
+        for field in &applied {
+            if field.is_shown(&applied) {
+                if let Some(message) = field.value_problem() {
+                    problems.push(InputProblem { input: Some(field.name.clone()), message });
+                }
+            } else if field.required && field.default_value.is_empty() {
+                let condition = field.show_if.as_ref().map(|c| format!(" (shown when {})", c)).unwrap_or_default();
+                problems.push(InputProblem {
+                    input: None,
+                    message: format!("input '{}' is required by the workflow but hidden{}", field.name, condition),
+                });
+            }
+        }
+    }
+
+    if sent.len() > limits.max_inputs {
+        problems.push(InputProblem {
+            input: None,
+            message: format!(
+                "{} inputs set, GitHub accepts at most {} per dispatch; clear optional ones",
+                sent.len(),
+                limits.max_inputs
+            ),
+        });
+    }
+
+    let size = payload_size(sent);
+    if size > limits.max_payload
+        && let Some((name, value)) = sent.iter().max_by_key(|(_, v)| v.chars().count()) {
+        problems.push(InputProblem {
+            input: Some(name.clone()),
+            message: format!(
+                "inputs are {} characters of JSON, GitHub accepts at most {}; this is the largest value ({} characters)",
+                size,
+                limits.max_payload,
+                value.chars().count()
+            ),
+        });
+    }
+
+    problems
+}
+
+/// The declared inputs holding the values of a dispatch; inputs it doesn't
+/// send are unset.
+pub fn with_values(fields: &[InputField], sent: &[(String, String)]) -> Vec<InputField> {
+    fields
+        .iter()
+        .map(|field| {
+            let mut field = field.clone();
+            match sent.iter().find(|(n, _)| *n == field.name) {
+                Some((_, value)) => field.set_value(value.clone()),
+                None => field.unset(),
+            }
+            field
+        })
+        .collect()
+}
+
+/// Characters of the `inputs` object GitHub receives.
+fn payload_size(sent: &[(String, String)]) -> usize {
+    let inputs: serde_json::Map<String, serde_json::Value> = sent
+        .iter()
+        .map(|(k, v)| (k.clone(), serde_json::Value::String(v.clone())))
+        .collect();
+    serde_json::Value::Object(inputs).to_string().chars().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::fixtures::input;
+    use crate::domain::InputCondition;
+
+    fn sent(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(n, v)| (n.to_string(), v.to_string())).collect()
+    }
+
+    fn problem(input: Option<&str>, message: &str) -> InputProblem {
+        InputProblem { input: input.map(str::to_string), message: message.to_string() }
+    }
+
+    #[test]
+    fn valid_inputs_have_no_problems() {
+        let fields = vec![input("deploy", "boolean", ""), input("replicas", "number", "")];
+        let sent = sent(&[("deploy", "true"), ("replicas", "2.5")]);
+        assert_eq!(check(&sent, Some(&fields), &LimitsConfig::default()), vec![]);
+    }
+
+    #[test]
+    fn reports_unknown_inputs_and_bad_values() {
+        let mut env = input("env", "choice", "");
+        env.options = vec!["staging".to_string(), "prod".to_string()];
+        let mut ticket = input("ticket", "string", "");
+        ticket.required = true;
+        let fields = vec![input("deploy", "boolean", ""), input("replicas", "number", ""), env, ticket];
+        let sent = sent(&[("deploy", "yes"), ("replicas", "NaN"), ("env", "dev"), ("typo", "1")]);
+
+        assert_eq!(
+            check(&sent, Some(&fields), &LimitsConfig::default()),
+            vec![
+                problem(Some("typo"), "not declared by the workflow on this ref"),
+                problem(Some("deploy"), "'yes' is not true or false"),
+                problem(Some("replicas"), "'NaN' is not a number"),
+                problem(Some("env"), "'dev' is not one of: staging, prod"),
+                problem(Some("ticket"), "required, but unset"),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_value_counts_as_set() {
+        let mut ticket = input("ticket", "string", "");
+        ticket.required = true;
+        let fields = vec![ticket];
+        assert_eq!(check(&sent(&[("ticket", "")]), Some(&fields), &LimitsConfig::default()), vec![]);
+    }
+
+    #[test]
+    fn hidden_inputs_skip_value_checks_but_not_required() {
+        let mut replicas = input("replicas", "number", "");
+        replicas.show_if = Some(InputCondition::parse("deploy").unwrap());
+        let mut region = input("region", "string", "");
+        region.required = true;
+        region.show_if = Some(InputCondition::parse("env=prod|staging").unwrap());
+        let mut zone = input("zone", "string", "a");
+        zone.required = true;
+        zone.show_if = Some(InputCondition::parse("deploy").unwrap());
+        let fields = vec![input("deploy", "boolean", ""), input("env", "string", ""), replicas, region, zone];
+
+        assert_eq!(
+            check(&sent(&[("deploy", "false"), ("env", "dev")]), Some(&fields), &LimitsConfig::default()),
+            vec![problem(None, "input 'region' is required by the workflow but hidden (shown when env=prod|staging)")]
+        );
+    }
+
+    #[test]
+    fn reports_limits() {
+        let limits = LimitsConfig { max_inputs: 2, max_payload: 30 };
+        let sent = sent(&[("a", "1"), ("b", "22"), ("c", "a longer value")]);
+        assert_eq!(payload_size(&sent), 39);
+        assert_eq!(
+            check(&sent, None, &limits),
+            vec![
+                problem(None, "3 inputs set, GitHub accepts at most 2 per dispatch; clear optional ones"),
+                problem(
+                    Some("c"),
+                    "inputs are 39 characters of JSON, GitHub accepts at most 30; this is the largest value (14 characters)"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn with_values_unsets_inputs_not_sent() {
+        let mut fields = vec![input("a", "string", ""), input("b", "string", "x")];
+        fields[1].set_value("y");
+        let applied = with_values(&fields, &sent(&[("a", "")]));
+        assert!(applied[0].is_set());
+        assert_eq!(applied[0].value, "");
+        assert!(!applied[1].is_set());
+        assert_eq!(applied[1].value, "x");
+    }
+}
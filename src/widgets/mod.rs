@@ -0,0 +1,3 @@
+pub mod text_input;
+
+pub use text_input::TextInput;
@@ -0,0 +1,269 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use unicode_width::UnicodeWidthChar;
+
+/// A single-line text field with a cursor, readline-style editing keys and
+/// horizontal scrolling when the value is wider than its area.
+#[derive(Debug, Clone, Default)]
+pub struct TextInput {
+    value: String,
+    cursor: usize, // in chars, 0..=len
+    offset: usize, // first visible char, kept so the view doesn't jump while typing
+}
+
+impl TextInput {
+    pub fn new(value: impl Into<String>) -> Self {
+        let mut input = Self::default();
+        input.set_value(value);
+        input
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    /// Replace the value and put the cursor at its end.
+    pub fn set_value(&mut self, value: impl Into<String>) {
+        self.value = value.into();
+        self.cursor = self.len();
+        self.offset = 0;
+    }
+
+    pub fn clear(&mut self) {
+        self.set_value(String::new());
+    }
+
+    pub fn insert(&mut self, c: char) {
+        let at = self.byte_index(self.cursor);
+        self.value.insert(at, c);
+        self.cursor += 1;
+    }
+
+    /// Insert pasted text at the cursor. Line breaks become spaces since
+    /// the field holds a single line.
+    pub fn insert_str(&mut self, text: &str) {
+        let text = text.trim_end_matches(['\r', '\n']).replace("\r\n", " ").replace(['\r', '\n'], " ");
+        let at = self.byte_index(self.cursor);
+        self.value.insert_str(at, &text);
+        self.cursor += text.chars().count();
+    }
+
+    /// Apply an editing key. Returns false for keys the field doesn't use,
+    /// so the caller can handle them.
+    pub fn handle_key(&mut self, key: &KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        match key.code {
+            KeyCode::Char('a') if ctrl => self.cursor = 0,
+            KeyCode::Char('e') if ctrl => self.cursor = self.len(),
+            KeyCode::Char('b') if ctrl => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Char('f') if ctrl => self.cursor = (self.cursor + 1).min(self.len()),
+            KeyCode::Char('b') if alt => self.cursor = self.word_start(),
+            KeyCode::Char('f') if alt => self.cursor = self.word_end(),
+            KeyCode::Char('w') if ctrl => self.delete_to(self.word_start()),
+            KeyCode::Backspace if ctrl || alt => self.delete_to(self.word_start()),
+            KeyCode::Char('u') if ctrl => self.delete_to(0),
+            KeyCode::Char('k') if ctrl => self.delete_to(self.len()),
+            KeyCode::Char('d') if ctrl => self.delete_to((self.cursor + 1).min(self.len())),
+            KeyCode::Char(c) if !ctrl && !alt => self.insert(c),
+            KeyCode::Backspace => self.delete_to(self.cursor.saturating_sub(1)),
+            KeyCode::Delete => self.delete_to((self.cursor + 1).min(self.len())),
+            KeyCode::Left if ctrl || alt => self.cursor = self.word_start(),
+            KeyCode::Right if ctrl || alt => self.cursor = self.word_end(),
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.len()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.len(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// The part of the value that fits in `width` columns, scrolled so the
+    /// cursor stays visible. Wide chars such as CJK or emoji take two
+    /// columns. The cursor is drawn only when `focused`.
+    pub fn line(&mut self, width: u16, style: Style, focused: bool) -> Line<'static> {
+        let width = usize::from(width).max(1);
+        let chars: Vec<char> = self.value.chars().map(display_char).collect();
+        if !focused {
+            self.offset = 0;
+            let visible: String = chars[..fitting(&chars, width)].iter().collect();
+            return Line::from(Span::styled(visible, style));
+        }
+
+        // The cursor needs its char's columns, or one past the last char
+        let cursor_width = chars.get(self.cursor).map_or(1, |&c| char_width(c).max(1));
+        if self.cursor < self.offset {
+            self.offset = self.cursor;
+        }
+        while self.offset < self.cursor
+            && columns(&chars[self.offset..self.cursor]) + cursor_width > width
+        {
+            self.offset += 1;
+        }
+
+        let before: String = chars[self.offset..self.cursor].iter().collect();
+        let mut spans = vec![Span::styled(before, style)];
+        match chars.get(self.cursor) {
+            Some(c) => {
+                spans.push(Span::styled(c.to_string(), style.add_modifier(Modifier::REVERSED)));
+                let rest = &chars[self.cursor + 1..];
+                let room = width.saturating_sub(columns(&chars[self.offset..=self.cursor]));
+                spans.push(Span::styled(rest[..fitting(rest, room)].iter().collect::<String>(), style));
+            }
+            None => spans.push(Span::styled("█", style)),
+        }
+        Line::from(spans)
+    }
+
+    fn len(&self) -> usize {
+        self.value.chars().count()
+    }
+
+    fn byte_index(&self, char_index: usize) -> usize {
+        self.value.char_indices().nth(char_index).map_or(self.value.len(), |(i, _)| i)
+    }
+
+    /// Delete between the cursor and `to`, leaving the cursor at the start.
+    fn delete_to(&mut self, to: usize) {
+        let (start, end) = if to < self.cursor { (to, self.cursor) } else { (self.cursor, to) };
+        let range = self.byte_index(start)..self.byte_index(end);
+        self.value.replace_range(range, "");
+        self.cursor = start;
+    }
+
+    /// Start of the word before the cursor, skipping whitespace first.
+    fn word_start(&self) -> usize {
+        let chars: Vec<char> = self.value.chars().collect();
+        let mut i = self.cursor;
+        while i > 0 && chars[i - 1].is_whitespace() {
+            i -= 1;
+        }
+        while i > 0 && !chars[i - 1].is_whitespace() {
+            i -= 1;
+        }
+        i
+    }
+
+    /// End of the word after the cursor, skipping whitespace first.
+    fn word_end(&self) -> usize {
+        let chars: Vec<char> = self.value.chars().collect();
+        let mut i = self.cursor;
+        while i < chars.len() && chars[i].is_whitespace() {
+            i += 1;
+        }
+        while i < chars.len() && !chars[i].is_whitespace() {
+            i += 1;
+        }
+        i
+    }
+}
+
+fn char_width(c: char) -> usize {
+    c.width().unwrap_or(0)
+}
+
+/// Display columns `chars` take up.
+fn columns(chars: &[char]) -> usize {
+    chars.iter().map(|&c| char_width(c)).sum()
+}
+
+/// How many of the leading `chars` fit in `width` columns.
+fn fitting(chars: &[char], width: usize) -> usize {
+    let mut used = 0;
+    chars
+        .iter()
+        .take_while(|&&c| {
+            used += char_width(c);
+            used <= width
+        })
+        .count()
+}
+
+/// Line breaks, e.g. in a value written in `$EDITOR`, shown as a visible mark.
+fn display_char(c: char) -> char {
+    if c == '\n' { '⏎' } else { c }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(input: &mut TextInput, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        input.handle_key(&KeyEvent::new(code, modifiers))
+    }
+
+    #[test]
+    fn edits_at_the_cursor() {
+        let mut input = TextInput::new("héllo");
+        assert_eq!(input.cursor, 5);
+        press(&mut input, KeyCode::Left, KeyModifiers::NONE);
+        press(&mut input, KeyCode::Left, KeyModifiers::NONE);
+        input.insert('X');
+        assert_eq!(input.value(), "hélXlo");
+        press(&mut input, KeyCode::Backspace, KeyModifiers::NONE);
+        press(&mut input, KeyCode::Backspace, KeyModifiers::NONE);
+        assert_eq!(input.value(), "hélo");
+        press(&mut input, KeyCode::Delete, KeyModifiers::NONE);
+        assert_eq!(input.value(), "héo");
+        press(&mut input, KeyCode::Home, KeyModifiers::NONE);
+        press(&mut input, KeyCode::Backspace, KeyModifiers::NONE);
+        assert_eq!(input.value(), "héo");
+        assert_eq!(input.cursor, 0);
+    }
+
+    #[test]
+    fn deletes_by_word_and_line() {
+        let mut input = TextInput::new("deploy to  prod");
+        press(&mut input, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(input.value(), "deploy to  ");
+        press(&mut input, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(input.value(), "deploy ");
+        press(&mut input, KeyCode::Char('a'), KeyModifiers::CONTROL);
+        press(&mut input, KeyCode::Char('f'), KeyModifiers::ALT);
+        assert_eq!(input.cursor, 6);
+        press(&mut input, KeyCode::Char('k'), KeyModifiers::CONTROL);
+        assert_eq!(input.value(), "deploy");
+        press(&mut input, KeyCode::Char('u'), KeyModifiers::CONTROL);
+        assert_eq!(input.value(), "");
+        assert!(!press(&mut input, KeyCode::Enter, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn pastes_a_single_line() {
+        let mut input = TextInput::new("ab");
+        press(&mut input, KeyCode::Left, KeyModifiers::NONE);
+        input.insert_str("1\r\n2\n");
+        assert_eq!(input.value(), "a1 2b");
+        assert_eq!(input.cursor, 4);
+    }
+
+    #[test]
+    fn scrolls_to_keep_the_cursor_visible() {
+        let mut input = TextInput::new("abcdefgh");
+        let text = |line: Line| line.spans.iter().map(|s| s.content.to_string()).collect::<String>();
+        assert_eq!(text(input.line(4, Style::default(), true)), "fgh█");
+
+        press(&mut input, KeyCode::Home, KeyModifiers::NONE);
+        assert_eq!(text(input.line(4, Style::default(), true)), "abcd");
+        assert_eq!(text(input.line(4, Style::default(), false)), "abcd");
+    }
+
+    #[test]
+    fn scrolls_by_display_width() {
+        let mut input = TextInput::new("日本語テキスト");
+        let line = input.line(6, Style::default(), true);
+        let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, "スト█");
+        assert_eq!(input.offset, 5);
+
+        let line = input.line(5, Style::default(), false);
+        let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, "日本");
+    }
+}